@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use bevy_ninepatch::{NinePatchBuilder, NinePatchBundle, NinePatchData, NinePatchPlugin};
+use bevy_ninepatch::{NinePatchBuilder, NinePatchData, NinePatchPlugin};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     App::default()
@@ -25,10 +25,10 @@ fn setup(
     let nine_patch_handle = nine_patches.add(NinePatchBuilder::by_margins(20, 20, 20, 20));
 
     commands.spawn(
-        // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+        // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
         // of this entity
-        NinePatchBundle {
-            style: Style {
+        (
+            Style {
                 margin: UiRect::all(Val::Auto),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
@@ -36,19 +36,18 @@ fn setup(
                 height: Val::Px(50.),
                 ..Default::default()
             },
-            nine_patch_data: NinePatchData {
+            NinePatchData {
                 nine_patch: nine_patch_handle,
                 texture: panel_texture_handle,
                 ..Default::default()
             },
-            ..Default::default()
-        },
+        ),
     );
 
     commands.spawn(Camera2dBundle::default());
 }
 
-// by changing the component `Style.size`, the 9-Patch UI element will be resized
+// by changing the `width` and `height` of the component `Style`, the 9-Patch UI element will be resized
 fn update_size(time: Res<Time>, mut query: Query<&mut Style, With<NinePatchData<()>>>) {
     for mut style in query.iter_mut() {
         let (x, y) = time.elapsed_seconds().sin_cos();
@@ -1,16 +1,13 @@
 use bevy::prelude::*;
 
-use bevy_ninepatch::{
-    NinePatchBuilder, NinePatchBundle, NinePatchContent, NinePatchData, NinePatchPlugin,
-};
+use bevy_ninepatch::{NinePatchBuilder, NinePatchData, NinePatchPlugin, NinePatchReady};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     App::default()
-        .add_plugins(DefaultPlugins)
         // Add the `NinePatchPlugin` plugin
-        .add_plugin(NinePatchPlugin::<()>::default())
-        .add_startup_system(setup)
-        .add_system(set_content)
+        .add_plugins((DefaultPlugins, NinePatchPlugin::<()>::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, set_content)
         .run();
 
     Ok(())
@@ -27,35 +24,36 @@ fn setup(
     let nine_patch_handle = nine_patches.add(NinePatchBuilder::by_margins(20, 20, 20, 20));
 
     commands.spawn(
-        // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+        // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
         // of this entity
-        NinePatchBundle {
-            style: Style {
+        (
+            Style {
                 margin: UiRect::all(Val::Auto),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
-                size: Size::new(Val::Px(500.), Val::Px(300.)),
+                width: Val::Px(500.),
+                height: Val::Px(300.),
                 ..Default::default()
             },
-            nine_patch_data: NinePatchData {
+            NinePatchData {
                 nine_patch: nine_patch_handle,
                 texture: panel_texture_handle,
                 ..Default::default()
             },
-            ..Default::default()
-        },
+        ),
     );
 
     commands.spawn(Camera2dBundle::default());
 }
 
+// the `NinePatchReady` event is sent once the 9-Patch UI element is built, with the entity of each content slot
 fn set_content(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    mut query: Query<(Entity, &mut NinePatchContent<()>)>,
+    mut ready_events: EventReader<NinePatchReady<()>>,
 ) {
-    for (entity, mut nine_patch_content) in query.iter_mut() {
-        if !nine_patch_content.loaded {
+    for ready in ready_events.read() {
+        if let Some(slot) = ready.slots.get(&()) {
             // load font
             let font = asset_server.load("Kenney Future Narrow.ttf");
 
@@ -69,8 +67,7 @@ fn set_content(
                     },
                 ))
                 .id();
-            commands.entity(entity).push_children(&[content_entity]);
-            nine_patch_content.loaded = true;
+            commands.entity(*slot).push_children(&[content_entity]);
         }
     }
 }
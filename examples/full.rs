@@ -35,106 +35,114 @@ fn setup(
         vec![
             // top left corner patch
             Patch {
-                original_size: IVec2::new(30, 35),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
+                original_width: PatchSize::Fixed(30),
+                original_height: PatchSize::Fixed(35),
+                target_width: PatchTarget::Source,
+                target_height: PatchTarget::Source,
                 content: None,
             },
-            // top middle-left patch. This patch width can grow, and will contain the content for
-            // `PanelContent::Title`
+            // top middle-left patch. This patch width can grow, taking 3 parts of the free space out of 10, and will
+            // contain the content for `PanelContent::Title`
             Patch {
-                original_size: IVec2::new(15, 35),
-                target_width: Val::Percent(30.),
-                target_height: Val::ZERO,
+                original_width: PatchSize::Fixed(15),
+                original_height: PatchSize::Fixed(35),
+                target_width: PatchTarget::Grow(3.),
+                target_height: PatchTarget::Source,
                 content: Some(Content::Title),
             },
             // top middle patch. In the original PNG, it's the yellow titled part
             Patch {
-                original_size: IVec2::new(25, 35),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
+                original_width: PatchSize::Fixed(25),
+                original_height: PatchSize::Fixed(35),
+                target_width: PatchTarget::Source,
+                target_height: PatchTarget::Source,
                 content: None,
             },
-            // top middle-right patch. This patch width can grow
+            // top middle-right patch. This patch width can grow, taking the 7 other parts of the free space
             Patch {
-                original_size: IVec2::new(20, 35),
-                target_width: Val::Percent(70.),
-                target_height: Val::ZERO,
+                original_width: PatchSize::Fixed(20),
+                original_height: PatchSize::Fixed(35),
+                target_width: PatchTarget::Grow(7.),
+                target_height: PatchTarget::Source,
                 content: None,
             },
             // top right corner
             Patch {
-                original_size: IVec2::new(10, 35),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
+                original_width: PatchSize::Fixed(10),
+                original_height: PatchSize::Fixed(35),
+                target_width: PatchTarget::Source,
+                target_height: PatchTarget::Source,
                 content: None,
             },
         ],
         vec![
             // left border. This patch height can grow
             Patch {
-                original_size: IVec2::new(10, -45),
-                target_width: Val::ZERO,
-                target_height: Val::Percent(100.),
+                original_width: PatchSize::Fixed(10),
+                original_height: PatchSize::Remaining(45),
+                target_width: PatchTarget::Source,
+                target_height: PatchTarget::Grow(1.),
                 content: None,
             },
             // center. This patch can grow both in height and width, and will contain `PanelContent::Body`
             Patch {
-                original_size: IVec2::new(-20, -45),
-                target_width: Val::Percent(100.),
-                target_height: Val::Percent(100.),
+                original_width: PatchSize::Remaining(20),
+                original_height: PatchSize::Remaining(45),
+                target_width: PatchTarget::Grow(1.),
+                target_height: PatchTarget::Grow(1.),
                 content: Some(Content::Content),
             },
             // right border. This patch height can grow
             Patch {
-                original_size: IVec2::new(10, -45),
-                target_width: Val::ZERO,
-                target_height: Val::Percent(100.),
+                original_width: PatchSize::Fixed(10),
+                original_height: PatchSize::Remaining(45),
+                target_width: PatchTarget::Source,
+                target_height: PatchTarget::Grow(1.),
                 content: None,
             },
         ],
         vec![
             // bottom left corner
             Patch {
-                original_size: IVec2::new(10, 10),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
+                original_width: PatchSize::Fixed(10),
+                original_height: PatchSize::Fixed(10),
+                target_width: PatchTarget::Source,
+                target_height: PatchTarget::Source,
                 content: None,
             },
             // bottom middle. This patch width can grow
             Patch {
-                original_size: IVec2::new(-20, 10),
-                target_width: Val::Percent(100.),
-                target_height: Val::ZERO,
+                original_width: PatchSize::Remaining(20),
+                original_height: PatchSize::Fixed(10),
+                target_width: PatchTarget::Grow(1.),
+                target_height: PatchTarget::Source,
                 content: None,
             },
             // bottom right corner
             Patch {
-                original_size: IVec2::new(10, 10),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
+                original_width: PatchSize::Fixed(10),
+                original_height: PatchSize::Fixed(10),
+                target_width: PatchTarget::Source,
+                target_height: PatchTarget::Source,
                 content: None,
             },
         ],
     ]));
 
     commands.spawn((
-        // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+        // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
         // of this entity
-        NinePatchBundle {
-            style: Style {
-                margin: UiRect::all(Val::Auto),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                width: Val::Px(900.),
-                height: Val::Px(600.),
-                ..Default::default()
-            },
-            nine_patch_data: NinePatchData {
-                nine_patch: panel_nine_patch_handle,
-                texture: cornered_panel_texture_handle,
-                ..Default::default()
-            },
+        Style {
+            margin: UiRect::all(Val::Auto),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            width: Val::Px(900.),
+            height: Val::Px(600.),
+            ..Default::default()
+        },
+        NinePatchData {
+            nine_patch: panel_nine_patch_handle,
+            texture: cornered_panel_texture_handle,
             ..Default::default()
         },
         UiElement::Panel,
@@ -172,22 +180,19 @@ fn set_content(
 
                     let content_entity = commands
                         .spawn((
-                            // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+                            // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
                             // of this entity
-                            NinePatchBundle {
-                                style: Style {
-                                    margin: UiRect::all(Val::Auto),
-                                    justify_content: JustifyContent::Center,
-                                    align_items: AlignItems::Center,
-                                    width: Val::Px(850.),
-                                    height: Val::Px(550.),
-                                    ..Default::default()
-                                },
-                                nine_patch_data: NinePatchData {
-                                    nine_patch: nine_patch_handle,
-                                    texture: panel_texture_handle,
-                                    ..Default::default()
-                                },
+                            Style {
+                                margin: UiRect::all(Val::Auto),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                width: Val::Px(850.),
+                                height: Val::Px(550.),
+                                ..Default::default()
+                            },
+                            NinePatchData {
+                                nine_patch: nine_patch_handle,
+                                texture: panel_texture_handle,
                                 ..Default::default()
                             },
                             UiElement::InnerPanel,
@@ -230,28 +235,25 @@ fn set_content(
 
                     let button_cancel_entity = commands
                         .spawn((
-                            // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+                            // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
                             // of this entity
-                            NinePatchBundle {
-                                style: Style {
-                                    margin: UiRect {
-                                        left: Val::Px(0.),
-                                        right: Val::Auto,
-                                        top: Val::Auto,
-                                        bottom: Val::Px(0.),
-                                    },
-                                    width: Val::Px(300.),
-                                    height: Val::Px(80.),
-
-                                    justify_content: JustifyContent::Center,
-                                    align_items: AlignItems::Center,
-                                    ..Default::default()
-                                },
-                                nine_patch_data: NinePatchData {
-                                    nine_patch: button_nine_patch_handle.clone(),
-                                    texture: button_texture_handle.clone(),
-                                    ..Default::default()
+                            Style {
+                                margin: UiRect {
+                                    left: Val::Px(0.),
+                                    right: Val::Auto,
+                                    top: Val::Auto,
+                                    bottom: Val::Px(0.),
                                 },
+                                width: Val::Px(300.),
+                                height: Val::Px(80.),
+
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            NinePatchData {
+                                nine_patch: button_nine_patch_handle.clone(),
+                                texture: button_texture_handle.clone(),
                                 ..Default::default()
                             },
                             UiElement::ButtonCancel,
@@ -260,27 +262,24 @@ fn set_content(
 
                     let button_ok_entity = commands
                         .spawn((
-                            // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+                            // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
                             // of this entity
-                            NinePatchBundle {
-                                style: Style {
-                                    margin: UiRect {
-                                        left: Val::Auto,
-                                        right: Val::Px(0.),
-                                        top: Val::Auto,
-                                        bottom: Val::Px(0.),
-                                    },
-                                    justify_content: JustifyContent::Center,
-                                    align_items: AlignItems::Center,
-                                    width: Val::Px(300.),
-                                    height: Val::Px(80.),
-                                    ..Default::default()
-                                },
-                                nine_patch_data: NinePatchData {
-                                    nine_patch: button_nine_patch_handle,
-                                    texture: button_texture_handle,
-                                    ..Default::default()
+                            Style {
+                                margin: UiRect {
+                                    left: Val::Auto,
+                                    right: Val::Px(0.),
+                                    top: Val::Auto,
+                                    bottom: Val::Px(0.),
                                 },
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                width: Val::Px(300.),
+                                height: Val::Px(80.),
+                                ..Default::default()
+                            },
+                            NinePatchData {
+                                nine_patch: button_nine_patch_handle,
+                                texture: button_texture_handle,
                                 ..Default::default()
                             },
                             UiElement::ButtonOK,
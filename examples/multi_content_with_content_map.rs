@@ -1,13 +1,12 @@
 use bevy::prelude::*;
 
-use bevy_ninepatch::{NinePatchBuilder, NinePatchBundle, NinePatchData, NinePatchPlugin};
+use bevy_ninepatch::{NinePatchBuilder, NinePatchData, NinePatchPlugin};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     App::default()
-        .add_plugins(DefaultPlugins)
         // Add the `NinePatchPlugin` plugin
-        .add_plugin(NinePatchPlugin::<()>::default())
-        .add_startup_system(setup)
+        .add_plugins((DefaultPlugins, NinePatchPlugin::<()>::default()))
+        .add_systems(Startup, setup)
         .run();
 
     Ok(())
@@ -53,10 +52,10 @@ fn setup(
 
     let button_entity = commands
         .spawn(
-            // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+            // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
             // of this entity
-            NinePatchBundle {
-                style: Style {
+            (
+                Style {
                     margin: UiRect {
                         left: Val::Auto,
                         right: Val::Px(0.),
@@ -65,17 +64,17 @@ fn setup(
                     },
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
-                    size: Size::new(Val::Px(200.), Val::Px(100.)),
+                    width: Val::Px(200.),
+                    height: Val::Px(100.),
                     ..Default::default()
                 },
-                nine_patch_data: NinePatchData {
+                NinePatchData {
                     texture: button_texture_handle,
                     nine_patch: button_nine_patch_handle,
                     content: Some(button_content),
                     ..Default::default()
                 },
-                ..Default::default()
-            },
+            ),
         )
         .id();
 
@@ -84,24 +83,24 @@ fn setup(
     let panel_nine_patch_handle = nine_patches.add(NinePatchBuilder::by_margins(20, 20, 20, 20));
 
     commands.spawn(
-        // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+        // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
         // of this entity
-        NinePatchBundle {
-            style: Style {
+        (
+            Style {
                 margin: UiRect::all(Val::Auto),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
-                size: Size::new(Val::Px(500.), Val::Px(300.)),
+                width: Val::Px(500.),
+                height: Val::Px(300.),
                 ..Default::default()
             },
             // helper method when there is only one content zone
-            nine_patch_data: NinePatchData::with_single_content(
+            NinePatchData::with_single_content(
                 panel_texture_handle,
                 panel_nine_patch_handle,
                 button_entity,
             ),
-            ..Default::default()
-        },
+        ),
     );
 
     commands.spawn(Camera2dBundle::default());
@@ -4,11 +4,10 @@ use bevy_ninepatch::*;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     App::default()
-        .add_plugins(DefaultPlugins)
         // Add the `NinePatchPlugin` plugin
-        .add_plugin(NinePatchPlugin::<()>::default())
-        .add_startup_system(setup)
-        .add_system(set_content)
+        .add_plugins((DefaultPlugins, NinePatchPlugin::<()>::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, set_content)
         .run();
 
     Ok(())
@@ -26,53 +25,49 @@ fn setup(
     let button_nine_patch_handle = nine_patches.add(NinePatchBuilder::by_margins(5, 10, 6, 6));
 
     commands.spawn((
-        // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+        // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
         // of this entity
-        NinePatchBundle {
-            style: Style {
-                margin: UiRect {
-                    left: Val::Px(0.),
-                    right: Val::Auto,
-                    top: Val::Auto,
-                    bottom: Val::Px(0.),
-                },
-                size: Size::new(Val::Px(300.), Val::Px(80.)),
-
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..Default::default()
-            },
-            nine_patch_data: NinePatchData {
-                nine_patch: button_nine_patch_handle.clone(),
-                texture: button_texture_handle.clone(),
-                ..Default::default()
+        Style {
+            margin: UiRect {
+                left: Val::Px(0.),
+                right: Val::Auto,
+                top: Val::Auto,
+                bottom: Val::Px(0.),
             },
+            width: Val::Px(300.),
+            height: Val::Px(80.),
+
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        NinePatchData {
+            nine_patch: button_nine_patch_handle.clone(),
+            texture: button_texture_handle.clone(),
             ..Default::default()
         },
         PatchElement::ButtonCancel,
     ));
     commands.spawn((
-        // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+        // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
         // of this entity
-        NinePatchBundle {
-            style: Style {
-                margin: UiRect {
-                    left: Val::Px(0.),
-                    right: Val::Auto,
-                    top: Val::Auto,
-                    bottom: Val::Px(0.),
-                },
-                size: Size::new(Val::Px(300.), Val::Px(80.)),
-
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..Default::default()
-            },
-            nine_patch_data: NinePatchData {
-                nine_patch: button_nine_patch_handle,
-                texture: button_texture_handle,
-                ..Default::default()
+        Style {
+            margin: UiRect {
+                left: Val::Px(0.),
+                right: Val::Auto,
+                top: Val::Auto,
+                bottom: Val::Px(0.),
             },
+            width: Val::Px(300.),
+            height: Val::Px(80.),
+
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        NinePatchData {
+            nine_patch: button_nine_patch_handle,
+            texture: button_texture_handle,
             ..Default::default()
         },
         PatchElement::ButtonOk,
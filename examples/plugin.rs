@@ -1,13 +1,12 @@
 use bevy::prelude::*;
 
-use bevy_ninepatch::{NinePatchBuilder, NinePatchBundle, NinePatchData, NinePatchPlugin};
+use bevy_ninepatch::{NinePatchBuilder, NinePatchData, NinePatchPlugin};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     App::default()
-        .add_plugins(DefaultPlugins)
         // Add the `NinePatchPlugin` plugin
-        .add_plugin(NinePatchPlugin::<()>::default())
-        .add_startup_system(setup)
+        .add_plugins((DefaultPlugins, NinePatchPlugin::<()>::default()))
+        .add_systems(Startup, setup)
         .run();
 
     Ok(())
@@ -24,23 +23,23 @@ fn setup(
     let nine_patch_handle = nine_patches.add(NinePatchBuilder::by_margins(20, 20, 20, 20));
 
     commands.spawn(
-        // this component bundle will be detected by the plugin, and the 9-Patch UI element will be added as a child
+        // these components will be detected by the plugin, and the 9-Patch UI element will be added as a child
         // of this entity
-        NinePatchBundle {
-            style: Style {
+        (
+            Style {
                 margin: UiRect::all(Val::Auto),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
-                size: Size::new(Val::Px(500.), Val::Px(300.)),
+                width: Val::Px(500.),
+                height: Val::Px(300.),
                 ..Default::default()
             },
-            nine_patch_data: NinePatchData {
+            NinePatchData {
                 nine_patch: nine_patch_handle,
                 texture: panel_texture_handle,
                 ..Default::default()
             },
-            ..Default::default()
-        },
+        ),
     );
 
     commands.spawn(Camera2dBundle::default());
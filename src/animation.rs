@@ -0,0 +1,271 @@
+use std::{collections::HashSet, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    fill::TiledPatch,
+    ninepatch::{GeneratedBy, PatchIndex},
+    NinePatchBuilder, NinePatchData,
+};
+
+/// A frame of a [`NinePatchAnimation`]
+#[derive(Debug, Clone)]
+pub struct NinePatchFrame {
+    /// Texture of the frame
+    pub texture: Handle<Image>,
+    /// Part of the texture holding the frame, for sprite sheets. It replaces the region of the `NinePatchBuilder`,
+    /// which is used if `None`
+    pub region: Option<URect>,
+}
+
+/// Animate a 9-Patch UI element by cycling through frames, added on the entity holding the `NinePatchData`. The
+/// layout of the patches is kept, only their textures change. The patches of each frame are split from its texture
+/// the first time it is displayed, and re-used after that. Frames that can't be split, because their patches are
+/// outside of their texture, are skipped: the patches keep the previous frame.
+#[derive(Debug, Clone, Component)]
+pub struct NinePatchAnimation {
+    /// Frames of the animation
+    pub frames: Vec<NinePatchFrame>,
+    /// Timer moving to the next frame each time it finishes. The animation stops on its last frame if the timer
+    /// doesn't repeat, and can be driven manually by pausing the timer and setting `frame`
+    pub timer: Timer,
+    /// Index of the frame displayed
+    pub frame: usize,
+    /// Textures of the patches of each frame, once split
+    splits: Vec<Option<Vec<Handle<Image>>>>,
+}
+
+/// Repeating timer of an animation at `fps` frames per second, paused if `fps` isn't a positive frame rate
+fn frame_timer(fps: f32) -> Timer {
+    match Duration::try_from_secs_f32(1. / fps) {
+        Ok(duration) if !duration.is_zero() => Timer::new(duration, TimerMode::Repeating),
+        _ => {
+            let mut timer = Timer::new(Duration::MAX, TimerMode::Repeating);
+            timer.pause();
+            timer
+        }
+    }
+}
+
+impl NinePatchAnimation {
+    /// Animate through `frames`, moving to the next frame when `timer` finishes
+    pub fn from_frames(frames: Vec<NinePatchFrame>, timer: Timer) -> Self {
+        NinePatchAnimation {
+            splits: vec![None; frames.len()],
+            frames,
+            timer,
+            frame: 0,
+        }
+    }
+
+    /// Animate through a list of images, looping at `fps` frames per second. The animation stays on its first frame if
+    /// `fps` isn't positive
+    pub fn from_images(textures: impl IntoIterator<Item = Handle<Image>>, fps: f32) -> Self {
+        Self::from_frames(
+            textures
+                .into_iter()
+                .map(|texture| NinePatchFrame {
+                    texture,
+                    region: None,
+                })
+                .collect(),
+            frame_timer(fps),
+        )
+    }
+
+    /// Animate through regions of a sprite sheet, looping at `fps` frames per second. The animation stays on its first frame if
+    /// `fps` isn't positive
+    pub fn from_regions(
+        texture: Handle<Image>,
+        regions: impl IntoIterator<Item = URect>,
+        fps: f32,
+    ) -> Self {
+        Self::from_frames(
+            regions
+                .into_iter()
+                .map(|region| NinePatchFrame {
+                    texture: texture.clone(),
+                    region: Some(region),
+                })
+                .collect(),
+            frame_timer(fps),
+        )
+    }
+
+    /// Animate through the textures of a texture atlas, looping at `fps` frames per second. The animation stays on its first frame if
+    /// `fps` isn't positive
+    pub fn from_texture_atlas(atlas: &TextureAtlas, fps: f32) -> Self {
+        Self::from_regions(
+            atlas.texture.clone(),
+            atlas
+                .textures
+                .iter()
+                .map(|rect| URect::from_corners(rect.min.as_uvec2(), rect.max.as_uvec2())),
+            fps,
+        )
+    }
+}
+
+pub(crate) fn animate_ninepatches<
+    T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static,
+>(
+    time: Res<Time>,
+    builders: Res<Assets<NinePatchBuilder<T>>>,
+    mut textures: ResMut<Assets<Image>>,
+    mut animations_query: Query<(Entity, &NinePatchData<T>, &mut NinePatchAnimation)>,
+    mut cells_query: Query<(
+        &GeneratedBy,
+        &PatchIndex,
+        &mut UiImage,
+        Option<&mut TiledPatch>,
+    )>,
+    mut invalid: Local<HashSet<(Entity, usize)>>,
+) {
+    for (entity, data, mut animation) in animations_query.iter_mut() {
+        if animation.is_added() {
+            // the entity may be re-used
+            invalid.retain(|(invalid_entity, _)| *invalid_entity != entity);
+        }
+        if animation.frames.is_empty() || !data.loaded {
+            continue;
+        }
+        let animation = animation.as_mut();
+        animation.timer.tick(time.delta());
+        let last = animation.frames.len() - 1;
+        for _ in 0..animation.timer.times_finished_this_tick() {
+            animation.frame = match animation.timer.mode() {
+                TimerMode::Repeating => (animation.frame + 1) % animation.frames.len(),
+                TimerMode::Once => (animation.frame + 1).min(last),
+            };
+        }
+        animation.frame = animation.frame.min(last);
+        animation.splits.resize(animation.frames.len(), None);
+
+        let frame = &animation.frames[animation.frame];
+        if animation.splits[animation.frame].is_none()
+            && !invalid.contains(&(entity, animation.frame))
+        {
+            let Some(builder) = builders.get(&data.nine_patch) else {
+                continue;
+            };
+            let Some(texture) = textures.get(&frame.texture) else {
+                // texture is not available yet, will try next loop
+                continue;
+            };
+            let size = texture.texture_descriptor.size;
+            if let Some(region) = frame
+                .region
+                .filter(|region| region.max.x > size.width || region.max.y > size.height)
+            {
+                // skipped, warned once
+                invalid.insert((entity, animation.frame));
+                warn!(
+                    "frame {} of a nine patch animation is outside of its texture: {region:?}",
+                    animation.frame
+                );
+                continue;
+            }
+            let split = builder.split(&frame.texture, frame.region, &mut textures);
+            if split.is_none() {
+                invalid.insert((entity, animation.frame));
+                warn!(
+                    "frame {} of a nine patch animation has patches outside of its texture",
+                    animation.frame
+                );
+            }
+            animation.splits[animation.frame] = split;
+        }
+    }
+
+    // patches generated again keep the current frame, as they are updated whenever their texture differs
+    for (generated_by, index, mut image, tiled) in cells_query.iter_mut() {
+        let Some(texture) = animations_query
+            .get(generated_by.0)
+            .ok()
+            .and_then(|(_, _, animation)| animation.splits.get(animation.frame)?.as_ref())
+            .and_then(|split| split.get(index.0))
+        else {
+            continue;
+        };
+        if image.texture.id() != texture.id() {
+            image.texture = texture.clone_weak();
+        }
+        if let Some(mut tiled) = tiled {
+            if tiled.texture.id() != texture.id() {
+                tiled.texture = texture.clone_weak();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::NinePatchAnimation;
+    use crate::{ninepatch::GeneratedBy, testing, NinePatchBuilder};
+
+    #[test]
+    fn invalid_frame_rates_are_paused() {
+        let frames = || [Handle::<Image>::default(), Handle::default()];
+        assert!(!NinePatchAnimation::from_images(frames(), 10.)
+            .timer
+            .paused());
+        for fps in [0., -10., f32::NAN, f32::INFINITY] {
+            let animation = NinePatchAnimation::from_images(frames(), fps);
+            assert!(animation.timer.paused(), "{fps} fps");
+        }
+    }
+
+    #[test]
+    fn frames_that_cant_be_split_are_skipped() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(10, 10, 10, 10),
+            UVec2::new(30, 30),
+            Vec2::new(100., 100.),
+        );
+        let sheet = testing::texture(&mut app, UVec2::new(60, 30), [255; 4]);
+        let regions = [
+            URect::new(0, 0, 30, 30),
+            // the right patch is past the edge of the texture
+            URect::new(50, 0, 60, 30),
+            URect::new(50, 0, 70, 30),
+        ];
+        app.world
+            .entity_mut(root)
+            .insert(NinePatchAnimation::from_regions(sheet, regions, 0.));
+        let textures = |app: &mut App| {
+            let mut images_query = app.world.query::<(&GeneratedBy, &UiImage)>();
+            let mut textures: Vec<_> = images_query
+                .iter(&app.world)
+                .filter(|(generated_by, _)| generated_by.0 == root)
+                .map(|(_, image)| image.texture.id())
+                .collect();
+            textures.sort();
+            textures
+        };
+        for _ in 0..3 {
+            app.update();
+        }
+        let first_frame = textures(&mut app);
+        let animation = app.world.get::<NinePatchAnimation>(root).unwrap();
+        let split: Vec<_> = animation.splits[0]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(Handle::id)
+            .collect();
+        assert!(split.iter().all(|texture| first_frame.contains(texture)));
+
+        for frame in [1, 2] {
+            app.world.get_mut::<NinePatchAnimation>(root).unwrap().frame = frame;
+            app.update();
+            app.update();
+            let animation = app.world.get::<NinePatchAnimation>(root).unwrap();
+            assert!(animation.splits[frame].is_none());
+            assert_eq!(textures(&mut app), first_frame);
+        }
+    }
+}
@@ -0,0 +1,691 @@
+use std::{collections::HashMap, io::Read};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{plugin::init_string_builders, NinePatchBuilder, NinePatchError};
+
+/// Label of the image of the first frame, in an asset loaded from an `.aseprite` or `.ase` file
+pub const ASEPRITE_TEXTURE: &str = "texture";
+
+/// Nine patches read from the 9-slices of an Aseprite file or JSON export
+#[derive(Debug, Clone, TypePath, Asset)]
+pub struct AsepriteNinePatches {
+    /// Texture the nine patches apply to: the first frame of an Aseprite file, or the sprite sheet of a JSON export
+    pub texture: Handle<Image>,
+    /// `NinePatchBuilder` of each 9-slice, by slice name. Its content zone is also named after the slice
+    pub nine_patches: HashMap<String, Handle<NinePatchBuilder<String>>>,
+}
+
+/// Plugin to load 9-slices of Aseprite files (`.aseprite`, `.ase`) and Aseprite JSON exports (`.aseprite.json`) as
+/// [`AsepriteNinePatches`]. Each slice is also available as a labeled `NinePatchBuilder<String>` asset named after
+/// it, and the image of an Aseprite file with the label [`ASEPRITE_TEXTURE`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NinePatchAsepritePlugin;
+
+impl Plugin for NinePatchAsepritePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AsepriteNinePatches>()
+            .init_asset_loader::<AsepriteNinePatchLoader>();
+    }
+
+    fn finish(&self, app: &mut App) {
+        init_string_builders(app);
+    }
+}
+
+impl NinePatchBuilder<String> {
+    /// Create a `NinePatchBuilder` from an Aseprite 9-slice, with `bounds` the slice in the texture and `center` its
+    /// stretchable center relative to `bounds`. The center is the content zone `name`.
+    pub fn from_aseprite_slice(name: &str, bounds: URect, center: URect) -> Self {
+        NinePatchBuilder::by_margins_with_content(
+            center.min.y,
+            bounds.height().saturating_sub(center.max.y),
+            center.min.x,
+            bounds.width().saturating_sub(center.max.x),
+            name.to_string(),
+        )
+        .with_region(bounds)
+    }
+}
+
+/// A 9-slice, with its bounds in the texture and its center relative to its bounds
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AsepriteSlice {
+    name: String,
+    bounds: URect,
+    center: URect,
+}
+
+fn invalid(reason: impl Into<String>) -> NinePatchError {
+    NinePatchError::InvalidAseprite(reason.into())
+}
+
+/// Sum of `a` and `b`, or `None` if it overflows
+fn checked_add(a: UVec2, b: UVec2) -> Option<UVec2> {
+    Some(UVec2::new(a.x.checked_add(b.x)?, a.y.checked_add(b.y)?))
+}
+
+fn slice(
+    name: String,
+    position: IVec2,
+    size: UVec2,
+    center: (IVec2, UVec2),
+) -> Result<AsepriteSlice, NinePatchError> {
+    let (center_position, center_size) = center;
+    let min = position.as_uvec2();
+    let max = checked_add(min, size);
+    let Some(max) = max.filter(|_| position.cmpge(IVec2::ZERO).all()) else {
+        return Err(invalid(format!("slice {name} is outside of the sprite")));
+    };
+    let center_min = center_position.as_uvec2();
+    let center_max = checked_add(center_min, center_size);
+    let Some(center_max) = center_max.filter(|center_max| {
+        center_position.cmpge(IVec2::ZERO).all() && center_max.cmple(size).all()
+    }) else {
+        return Err(invalid(format!(
+            "the center of slice {name} is outside of the slice"
+        )));
+    };
+    Ok(AsepriteSlice {
+        name,
+        bounds: URect::from_corners(min, max),
+        center: URect::from_corners(center_min, center_max),
+    })
+}
+
+/// Little endian reader over the bytes of an Aseprite file
+struct Bytes<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Bytes<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], NinePatchError> {
+        if self.data.len() < count {
+            return Err(invalid("the file is truncated"));
+        }
+        let (taken, rest) = self.data.split_at(count);
+        self.data = rest;
+        Ok(taken)
+    }
+    fn u8(&mut self) -> Result<u8, NinePatchError> {
+        Ok(self.take(1)?[0])
+    }
+    fn u16(&mut self) -> Result<u16, NinePatchError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+    fn i16(&mut self) -> Result<i16, NinePatchError> {
+        Ok(i16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+    fn u32(&mut self) -> Result<u32, NinePatchError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+    fn i32(&mut self) -> Result<i32, NinePatchError> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+    fn string(&mut self) -> Result<String, NinePatchError> {
+        let length = self.u16()? as usize;
+        Ok(String::from_utf8_lossy(self.take(length)?).into_owned())
+    }
+}
+
+struct Layer {
+    visible: bool,
+    background: bool,
+    opacity: u8,
+}
+
+struct Cel {
+    layer: usize,
+    position: IVec2,
+    z_index: i16,
+    opacity: u8,
+    size: UVec2,
+    pixels: Vec<u8>,
+}
+
+/// Image of the first frame of an Aseprite file, with its size, and the 9-slices of the file
+///
+/// Visible layers are composited with the normal blend mode, whatever their blend mode in Aseprite.
+fn read_aseprite(data: &[u8]) -> Result<(UVec2, Vec<u8>, Vec<AsepriteSlice>), NinePatchError> {
+    let mut header = Bytes { data };
+    header.take(4)?;
+    if header.u16()? != 0xA5E0 {
+        return Err(invalid("not an Aseprite file"));
+    }
+    let frames = header.u16()?;
+    let size = UVec2::new(header.u16()? as u32, header.u16()? as u32);
+    let depth = header.u16()?;
+    let layer_opacity_valid = header.u32()? & 1 != 0;
+    header.take(10)?;
+    let transparent_index = header.u8()?;
+    header.take(128 - 29)?;
+    let image_size = size
+        .x
+        .checked_mul(size.y)
+        .and_then(|pixels| pixels.checked_mul(4))
+        .ok_or_else(|| invalid("the sprite is too large"))?;
+    let bytes_per_pixel = match depth {
+        32 => 4,
+        16 => 2,
+        8 => 1,
+        _ => return Err(invalid(format!("unknown color depth {depth}"))),
+    };
+
+    let mut layers: Vec<Layer> = vec![];
+    let mut groups_visible: Vec<bool> = vec![];
+    let mut palette: Vec<[u8; 4]> = vec![];
+    let mut cels: Vec<Cel> = vec![];
+    let mut slices = vec![];
+    let mut rest = header;
+    for frame in 0..frames {
+        let mut frame_header = Bytes {
+            data: rest.take(16)?,
+        };
+        let frame_size = frame_header.u32()? as usize;
+        if frame_header.u16()? != 0xF1FA || frame_size < 16 {
+            return Err(invalid(format!("frame {frame} is invalid")));
+        }
+        let old_chunk_count = frame_header.u16()? as u32;
+        frame_header.take(4)?;
+        let chunk_count = match frame_header.u32()? {
+            0 => old_chunk_count,
+            count => count,
+        };
+        let mut chunks = Bytes {
+            data: rest.take(frame_size - 16)?,
+        };
+        for _ in 0..chunk_count {
+            let chunk_size = chunks.u32()? as usize;
+            let kind = chunks.u16()?;
+            let mut chunk = Bytes {
+                data: chunks.take(chunk_size.saturating_sub(6))?,
+            };
+            match kind {
+                // layer
+                0x2004 => {
+                    let flags = chunk.u16()?;
+                    chunk.take(2)?;
+                    let child_level = chunk.u16()? as usize;
+                    chunk.take(6)?;
+                    let opacity = chunk.u8()?;
+                    // a layer is only visible if all the groups containing it are
+                    groups_visible.truncate(child_level);
+                    let visible = flags & 1 != 0 && groups_visible.iter().all(|visible| *visible);
+                    groups_visible.push(flags & 1 != 0);
+                    layers.push(Layer {
+                        visible,
+                        background: flags & 8 != 0,
+                        opacity: if layer_opacity_valid { opacity } else { 255 },
+                    });
+                }
+                // cel, only the first frame is used
+                0x2005 if frame == 0 => {
+                    let layer = chunk.u16()? as usize;
+                    let position = IVec2::new(chunk.i16()? as i32, chunk.i16()? as i32);
+                    let opacity = chunk.u8()?;
+                    let cel_type = chunk.u16()?;
+                    let z_index = chunk.i16()?;
+                    chunk.take(5)?;
+                    if cel_type != 0 && cel_type != 2 {
+                        // linked cels and tilemaps
+                        continue;
+                    }
+                    let cel_size = UVec2::new(chunk.u16()? as u32, chunk.u16()? as u32);
+                    let pixels = if cel_type == 0 {
+                        chunk.data.to_vec()
+                    } else {
+                        let mut pixels = vec![];
+                        flate2::read::ZlibDecoder::new(chunk.data)
+                            .read_to_end(&mut pixels)
+                            .map_err(|error| invalid(format!("can't decompress a cel: {error}")))?;
+                        pixels
+                    };
+                    if pixels.len() < cel_size.x as usize * cel_size.y as usize * bytes_per_pixel {
+                        return Err(invalid("a cel is smaller than its size"));
+                    }
+                    cels.push(Cel {
+                        layer,
+                        position,
+                        z_index,
+                        opacity,
+                        size: cel_size,
+                        pixels,
+                    });
+                }
+                // palette
+                0x2019 => {
+                    let palette_size = chunk.u32()? as usize;
+                    let first = chunk.u32()? as usize;
+                    let last = chunk.u32()? as usize;
+                    chunk.take(8)?;
+                    palette.resize(palette_size.max(palette.len()), [0, 0, 0, 255]);
+                    for index in first..=last {
+                        let flags = chunk.u16()?;
+                        let color = chunk.take(4)?;
+                        if let Some(entry) = palette.get_mut(index) {
+                            entry.copy_from_slice(color);
+                        }
+                        if flags & 1 != 0 {
+                            chunk.string()?;
+                        }
+                    }
+                }
+                // slice
+                0x2022 => {
+                    let key_count = chunk.u32()?;
+                    let flags = chunk.u32()?;
+                    chunk.take(4)?;
+                    let name = chunk.string()?;
+                    let mut first_key = None;
+                    for _ in 0..key_count {
+                        let key_frame = chunk.u32()?;
+                        let position = IVec2::new(chunk.i32()?, chunk.i32()?);
+                        let slice_size = UVec2::new(chunk.u32()?, chunk.u32()?);
+                        let center = if flags & 1 != 0 {
+                            Some((
+                                IVec2::new(chunk.i32()?, chunk.i32()?),
+                                UVec2::new(chunk.u32()?, chunk.u32()?),
+                            ))
+                        } else {
+                            None
+                        };
+                        if flags & 2 != 0 {
+                            chunk.take(8)?;
+                        }
+                        if key_frame == 0 {
+                            first_key = Some((position, slice_size, center));
+                        }
+                    }
+                    // slices without 9-slice data aren't nine patches
+                    if let Some((position, slice_size, Some(center))) = first_key {
+                        let slice = slice(name, position, slice_size, center)?;
+                        if slice.bounds.max.cmpgt(size).any() {
+                            return Err(invalid(format!(
+                                "slice {} is outside of the sprite",
+                                slice.name
+                            )));
+                        }
+                        slices.push(slice);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // cels are drawn in layer order, moved by their z-index
+    cels.sort_by_key(|cel| (cel.layer as i32 + cel.z_index as i32, cel.z_index));
+    let mut image = vec![0; image_size as usize];
+    for cel in cels {
+        let Some(layer) = layers.get(cel.layer).filter(|layer| layer.visible) else {
+            continue;
+        };
+        let opacity = cel.opacity as f32 / 255. * layer.opacity as f32 / 255.;
+        for y in 0..cel.size.y {
+            for x in 0..cel.size.x {
+                let target = cel.position + UVec2::new(x, y).as_ivec2();
+                if target.cmplt(IVec2::ZERO).any() || target.as_uvec2().cmpge(size).any() {
+                    continue;
+                }
+                let i = ((y * cel.size.x + x) as usize) * bytes_per_pixel;
+                let source = match depth {
+                    32 => [
+                        cel.pixels[i],
+                        cel.pixels[i + 1],
+                        cel.pixels[i + 2],
+                        cel.pixels[i + 3],
+                    ],
+                    16 => [
+                        cel.pixels[i],
+                        cel.pixels[i],
+                        cel.pixels[i],
+                        cel.pixels[i + 1],
+                    ],
+                    _ if cel.pixels[i] == transparent_index && !layer.background => [0; 4],
+                    _ => palette
+                        .get(cel.pixels[i] as usize)
+                        .copied()
+                        .unwrap_or([0, 0, 0, 255]),
+                };
+                let j = ((target.y as u32 * size.x + target.x as u32) * 4) as usize;
+                blend(&mut image[j..j + 4], source, opacity);
+            }
+        }
+    }
+    Ok((size, image, slices))
+}
+
+/// Draw `source` over `target` with the normal blend mode
+fn blend(target: &mut [u8], source: [u8; 4], opacity: f32) {
+    let source_alpha = source[3] as f32 / 255. * opacity;
+    let target_alpha = target[3] as f32 / 255.;
+    let alpha = source_alpha + target_alpha * (1. - source_alpha);
+    if alpha <= 0. {
+        return;
+    }
+    for channel in 0..3 {
+        target[channel] = ((source[channel] as f32 * source_alpha
+            + target[channel] as f32 * target_alpha * (1. - source_alpha))
+            / alpha)
+            .round() as u8;
+    }
+    target[3] = (alpha * 255.).round() as u8;
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct JsonRect {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonFrame {
+    frame: JsonRect,
+    #[serde(default)]
+    rotated: bool,
+    sprite_source_size: JsonRect,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonFrames {
+    Array(Vec<JsonFrame>),
+    Hash(serde_json::Map<String, serde_json::Value>),
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonSliceKey {
+    frame: usize,
+    bounds: JsonRect,
+    center: Option<JsonRect>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonSlice {
+    name: String,
+    keys: Vec<JsonSliceKey>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonMeta {
+    image: String,
+    #[serde(default)]
+    slices: Vec<JsonSlice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonExport {
+    frames: JsonFrames,
+    meta: JsonMeta,
+}
+
+/// Path of the sprite sheet of an Aseprite JSON export, relative to the export, and its 9-slices placed in the
+/// sprite sheet
+fn read_aseprite_json(data: &[u8]) -> Result<(String, Vec<AsepriteSlice>), NinePatchError> {
+    let export: JsonExport =
+        serde_json::from_slice(data).map_err(|error| invalid(error.to_string()))?;
+    let frames = match export.frames {
+        JsonFrames::Array(frames) => frames,
+        JsonFrames::Hash(frames) => frames
+            .into_iter()
+            .map(|(_, frame)| serde_json::from_value(frame))
+            .collect::<Result<_, _>>()
+            .map_err(|error| invalid(error.to_string()))?,
+    };
+
+    let mut slices = vec![];
+    for json_slice in export.meta.slices {
+        let Some(key) = json_slice.keys.first() else {
+            continue;
+        };
+        // slices without 9-slice data aren't nine patches
+        let Some(center) = key.center else {
+            continue;
+        };
+        let frame = frames.get(key.frame).ok_or_else(|| {
+            invalid(format!(
+                "slice {} is on frame {}, which is not in the export",
+                json_slice.name, key.frame
+            ))
+        })?;
+        if frame.rotated {
+            return Err(invalid(format!(
+                "frame {} is rotated in the sprite sheet",
+                key.frame
+            )));
+        }
+        // the frame may have been trimmed in the sprite sheet
+        let source = frame.sprite_source_size;
+        if key.bounds.x < source.x
+            || key.bounds.y < source.y
+            || key.bounds.x as i64 + key.bounds.w as i64 > source.x as i64 + source.w as i64
+            || key.bounds.y as i64 + key.bounds.h as i64 > source.y as i64 + source.h as i64
+        {
+            return Err(invalid(format!(
+                "slice {} is cut by the trimming of frame {}",
+                json_slice.name, key.frame
+            )));
+        }
+        let x = i32::try_from(frame.frame.x as i64 + key.bounds.x as i64 - source.x as i64);
+        let y = i32::try_from(frame.frame.y as i64 + key.bounds.y as i64 - source.y as i64);
+        let (Ok(x), Ok(y)) = (x, y) else {
+            return Err(invalid(format!(
+                "slice {} is outside of the sprite sheet",
+                json_slice.name
+            )));
+        };
+        slices.push(slice(
+            json_slice.name,
+            IVec2::new(x, y),
+            UVec2::new(key.bounds.w, key.bounds.h),
+            (
+                IVec2::new(center.x, center.y),
+                UVec2::new(center.w, center.h),
+            ),
+        )?);
+    }
+    Ok((export.meta.image, slices))
+}
+
+/// Asset loader for the 9-slices of Aseprite files and JSON exports
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AsepriteNinePatchLoader;
+
+impl AssetLoader for AsepriteNinePatchLoader {
+    type Asset = AsepriteNinePatches;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = vec![];
+            reader.read_to_end(&mut bytes).await?;
+            let is_json = load_context
+                .path()
+                .extension()
+                .is_some_and(|extension| extension == "json");
+            let (texture, slices) = if is_json {
+                let (image, slices) = read_aseprite_json(&bytes)?;
+                let image_path = match load_context.path().parent() {
+                    Some(directory) => directory.join(image),
+                    None => image.into(),
+                };
+                (load_context.load(image_path), slices)
+            } else {
+                let (size, data, slices) = read_aseprite(&bytes)?;
+                let image = Image::new(
+                    Extent3d {
+                        width: size.x,
+                        height: size.y,
+                        depth_or_array_layers: 1,
+                    },
+                    TextureDimension::D2,
+                    data,
+                    TextureFormat::Rgba8UnormSrgb,
+                );
+                (
+                    load_context.add_labeled_asset(ASEPRITE_TEXTURE.to_string(), image),
+                    slices,
+                )
+            };
+            let nine_patches = slices
+                .into_iter()
+                .map(|slice| {
+                    let builder = NinePatchBuilder::from_aseprite_slice(
+                        &slice.name,
+                        slice.bounds,
+                        slice.center,
+                    );
+                    let handle = load_context.add_labeled_asset(slice.name.clone(), builder);
+                    (slice.name, handle)
+                })
+                .collect();
+            Ok(AsepriteNinePatches {
+                texture,
+                nine_patches,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite", "ase", "aseprite.json"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{invalid, read_aseprite, read_aseprite_json, AsepriteSlice};
+
+    const ASEPRITE: &[u8] = include_bytes!("../assets/tests/button.aseprite");
+    const JSON: &[u8] = include_bytes!("../assets/tests/button.aseprite.json");
+
+    fn button(position: UVec2) -> AsepriteSlice {
+        AsepriteSlice {
+            name: "button".to_string(),
+            bounds: URect::from_corners(position, position + UVec2::splat(6)),
+            center: URect::new(2, 2, 4, 4),
+        }
+    }
+
+    #[test]
+    fn aseprite_file() {
+        let (size, image, slices) = read_aseprite(ASEPRITE).unwrap();
+        assert_eq!(size, UVec2::new(8, 8));
+        // the compressed cel is drawn, the cel in the hidden group isn't
+        assert!(image.chunks(4).all(|pixel| pixel == [255, 0, 0, 255]));
+        assert_eq!(slices, vec![button(UVec2::ONE)]);
+    }
+
+    #[test]
+    fn truncated_aseprite_file() {
+        assert_eq!(
+            read_aseprite(&ASEPRITE[..ASEPRITE.len() - 10]),
+            Err(invalid("the file is truncated"))
+        );
+    }
+
+    #[test]
+    fn aseprite_json_export() {
+        let (image, slices) = read_aseprite_json(JSON).unwrap();
+        assert_eq!(image, "button.png");
+        // the slice is on the second frame, trimmed to its bounds
+        assert_eq!(slices, vec![button(UVec2::new(8, 0))]);
+    }
+
+    #[test]
+    fn oversized_aseprite_file() {
+        // width and height in the header
+        let mut data = ASEPRITE.to_vec();
+        data[8..12].copy_from_slice(&[0xFF; 4]);
+        assert_eq!(
+            read_aseprite(&data),
+            Err(invalid("the sprite is too large"))
+        );
+    }
+
+    #[test]
+    fn oversized_slice() {
+        let json = |frame_x: i32, bounds: &str, center: &str| {
+            format!(
+                r#"{{
+                    "frames": [{{
+                        "frame": {{ "x": {frame_x}, "y": 0, "w": 8, "h": 8 }},
+                        "spriteSourceSize": {{ "x": 0, "y": 0, "w": 4294967295, "h": 4294967295 }}
+                    }}],
+                    "meta": {{
+                        "image": "button.png",
+                        "slices": [{{ "name": "button", "keys": [{{
+                            "frame": 0,
+                            "bounds": {bounds},
+                            "center": {center}
+                        }}] }}]
+                    }}
+                }}"#
+            )
+        };
+        assert_eq!(
+            read_aseprite_json(
+                json(
+                    i32::MAX,
+                    r#"{ "x": 0, "y": 1, "w": 4294967295, "h": 6 }"#,
+                    r#"{ "x": 2, "y": 2, "w": 2, "h": 2 }"#
+                )
+                .as_bytes()
+            ),
+            Err(invalid("slice button is outside of the sprite"))
+        );
+        assert_eq!(
+            read_aseprite_json(
+                json(
+                    0,
+                    r#"{ "x": 1, "y": 1, "w": 6, "h": 6 }"#,
+                    r#"{ "x": 2, "y": 2, "w": 4294967295, "h": 2 }"#
+                )
+                .as_bytes()
+            ),
+            Err(invalid(
+                "the center of slice button is outside of the slice"
+            ))
+        );
+    }
+
+    #[test]
+    fn center_outside_of_slice() {
+        let json = r#"{
+            "frames": [{
+                "frame": { "x": 0, "y": 0, "w": 8, "h": 8 },
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 8, "h": 8 }
+            }],
+            "meta": {
+                "image": "button.png",
+                "slices": [{ "name": "button", "keys": [{
+                    "frame": 0,
+                    "bounds": { "x": 1, "y": 1, "w": 6, "h": 6 },
+                    "center": { "x": 2, "y": 2, "w": 6, "h": 2 }
+                }] }]
+            }
+        }"#;
+        assert_eq!(
+            read_aseprite_json(json.as_bytes()),
+            Err(invalid(
+                "the center of slice button is outside of the slice"
+            ))
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+
+use bevy::{
+    ecs::system::{EntityCommand, EntityCommands},
+    hierarchy::despawn_with_children_recursive,
+    prelude::*,
+};
+
+use crate::{ninepatch::GeneratedBy, NinePatchData};
+
+/// Event sent when a 9-Patch UI element has been built, with the entities of its content slots
+#[derive(Debug, Clone, Event)]
+pub struct NinePatchReady<T: Clone + Send + Sync + Eq + std::hash::Hash + 'static> {
+    /// Entity holding the `NinePatchData`
+    pub entity: Entity,
+    /// Entity of each content slot, by content key
+    pub slots: HashMap<T, Entity>,
+}
+
+/// Component added to a 9-Patch UI element once built, to find the entity of each of its content slots
+#[derive(Debug, Clone, Component)]
+pub struct NinePatchSlots<T: Clone + Send + Sync + Eq + std::hash::Hash + 'static> {
+    pub(crate) slots: HashMap<T, Entity>,
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + 'static> NinePatchSlots<T> {
+    /// Get the entity of the content slot for this key
+    pub fn get(&self, content: &T) -> Option<Entity> {
+        self.slots.get(content).copied()
+    }
+
+    /// Iterate over all content slots
+    pub fn iter(&self) -> impl Iterator<Item = (&T, Entity)> {
+        self.slots
+            .iter()
+            .map(|(content, entity)| (content, *entity))
+    }
+}
+
+/// What to do with the entity previously placed in a content slot when it is replaced or cleared. Content is detached
+/// by default, so that entities are only despawned when asked to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentPolicy {
+    /// Despawn the previous content and all its descendants
+    Despawn,
+    /// Remove the previous content from the slot, but keep it alive
+    #[default]
+    Detach,
+}
+
+/// Extension trait for `EntityCommands` to change the content of a 9-Patch UI element. This works whether the
+/// element is already built or not: if not, the change is kept in `NinePatchData::content` until it is.
+pub trait NinePatchCommandsExt<T> {
+    /// Place `entity` in the content slot `content`, handling what was there before according to `previous`
+    fn set_nine_patch_content(
+        &mut self,
+        content: T,
+        entity: Entity,
+        previous: ContentPolicy,
+    ) -> &mut Self;
+
+    /// Empty the content slot `content`, handling what was there before according to `previous`
+    fn clear_nine_patch_content(&mut self, content: T, previous: ContentPolicy) -> &mut Self;
+}
+
+impl<'w, 's, 'a, T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static>
+    NinePatchCommandsExt<T> for EntityCommands<'w, 's, 'a>
+{
+    fn set_nine_patch_content(
+        &mut self,
+        content: T,
+        entity: Entity,
+        previous: ContentPolicy,
+    ) -> &mut Self {
+        self.add(ReplaceContent {
+            content,
+            entity: Some(entity),
+            previous,
+        })
+    }
+
+    fn clear_nine_patch_content(&mut self, content: T, previous: ContentPolicy) -> &mut Self {
+        self.add(ReplaceContent {
+            content,
+            entity: None,
+            previous,
+        })
+    }
+}
+
+struct ReplaceContent<T> {
+    content: T,
+    entity: Option<Entity>,
+    previous: ContentPolicy,
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> EntityCommand
+    for ReplaceContent<T>
+{
+    fn apply(self, root: Entity, world: &mut World) {
+        replace_content(world, root, self.content, self.entity, self.previous);
+    }
+}
+
+fn remove_content(world: &mut World, entity: Entity, policy: ContentPolicy) {
+    match policy {
+        ContentPolicy::Despawn => despawn_with_children_recursive(world, entity),
+        ContentPolicy::Detach => {
+            if let Some(mut entity) = world.get_entity_mut(entity) {
+                entity.remove_parent();
+            }
+        }
+    }
+}
+
+fn replace_content<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static>(
+    world: &mut World,
+    root: Entity,
+    content: T,
+    entity: Option<Entity>,
+    previous: ContentPolicy,
+) {
+    let Some(mut root_entity) = world.get_entity_mut(root) else {
+        warn!("can't change the content of 9-Patch UI element {root:?}, it doesn't exist");
+        return;
+    };
+    let slot = root_entity
+        .get::<NinePatchSlots<T>>()
+        .and_then(|slots| slots.get(&content));
+    let Some(mut data) = root_entity.get_mut::<NinePatchData<T>>() else {
+        warn!("can't change the content of {root:?}, it isn't a 9-Patch UI element");
+        return;
+    };
+    // keep the content map up to date, it's used when the element is built
+    let replaced = match entity {
+        Some(entity) => data
+            .content
+            .get_or_insert_with(Default::default)
+            .insert(content, entity),
+        None => data.content.as_mut().and_then(|map| map.remove(&content)),
+    };
+
+    match slot {
+        Some(slot) => {
+            let occupants = world
+                .get::<Children>(slot)
+                .map(|children| children.to_vec())
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|occupant| !world.entity(*occupant).contains::<GeneratedBy>())
+                .collect::<Vec<_>>();
+            for occupant in occupants.into_iter().filter(|o| Some(*o) != entity) {
+                remove_content(world, occupant, previous);
+            }
+            if let Some(entity) = entity {
+                world.entity_mut(slot).push_children(&[entity]);
+            }
+        }
+        None => {
+            // not built yet, the previous content was never placed in the element
+            if let Some(replaced) = replaced.filter(|r| Some(*r) != entity) {
+                if previous == ContentPolicy::Despawn {
+                    despawn_with_children_recursive(world, replaced);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::CommandQueue, prelude::*};
+
+    use crate::{
+        ninepatch::GeneratedBy, testing, ContentPolicy, NinePatchBuilder, NinePatchCommandsExt,
+        NinePatchData, NinePatchSlots,
+    };
+
+    fn spawn(app: &mut App) -> Entity {
+        testing::spawn(
+            app,
+            NinePatchBuilder::by_margins(10, 10, 10, 10),
+            UVec2::new(30, 30),
+            Vec2::new(100., 100.),
+        )
+    }
+
+    fn build(app: &mut App) {
+        app.update();
+        app.update();
+    }
+
+    fn set_content(app: &mut App, root: Entity, entity: Option<Entity>, previous: ContentPolicy) {
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &app.world);
+        match entity {
+            Some(entity) => commands
+                .entity(root)
+                .set_nine_patch_content((), entity, previous),
+            None => commands.entity(root).clear_nine_patch_content((), previous),
+        };
+        queue.apply(&mut app.world);
+    }
+
+    fn slot(app: &App, root: Entity) -> Entity {
+        let slots = app.world.get::<NinePatchSlots<()>>(root).unwrap();
+        slots.get(&()).unwrap()
+    }
+
+    fn parent(app: &App, entity: Entity) -> Option<Entity> {
+        app.world.get::<Parent>(entity).map(Parent::get)
+    }
+
+    #[test]
+    fn content_set_before_the_build_is_placed_when_built() {
+        let mut app = testing::app();
+        let root = spawn(&mut app);
+        let replaced = app.world.spawn(NodeBundle::default()).id();
+        let content = app.world.spawn(NodeBundle::default()).id();
+        set_content(&mut app, root, Some(replaced), ContentPolicy::Despawn);
+        set_content(&mut app, root, Some(content), ContentPolicy::Despawn);
+        let data = app.world.get::<NinePatchData<()>>(root).unwrap();
+        assert_eq!(data.content.as_ref().unwrap().get(&()), Some(&content));
+        assert!(app.world.get_entity(replaced).is_none());
+
+        build(&mut app);
+
+        assert_eq!(parent(&app, content), Some(slot(&app, root)));
+    }
+
+    #[test]
+    fn content_replaced_in_a_built_slot_follows_the_policy() {
+        for policy in [ContentPolicy::Despawn, ContentPolicy::Detach] {
+            let mut app = testing::app();
+            let root = spawn(&mut app);
+            let previous = app.world.spawn(NodeBundle::default()).id();
+            let previous_child = app.world.spawn(NodeBundle::default()).id();
+            app.world.entity_mut(previous).add_child(previous_child);
+            set_content(&mut app, root, Some(previous), ContentPolicy::Despawn);
+            build(&mut app);
+            assert_eq!(parent(&app, previous), Some(slot(&app, root)));
+
+            let content = app.world.spawn(NodeBundle::default()).id();
+            set_content(&mut app, root, Some(content), policy);
+
+            assert_eq!(parent(&app, content), Some(slot(&app, root)));
+            match policy {
+                ContentPolicy::Despawn => {
+                    assert!(app.world.get_entity(previous).is_none());
+                    assert!(app.world.get_entity(previous_child).is_none());
+                }
+                ContentPolicy::Detach => {
+                    assert_eq!(parent(&app, previous), None);
+                    assert_eq!(parent(&app, previous_child), Some(previous));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cleared_slot_is_empty() {
+        let mut app = testing::app();
+        let root = spawn(&mut app);
+        let content = app.world.spawn(NodeBundle::default()).id();
+        set_content(&mut app, root, Some(content), ContentPolicy::Despawn);
+        build(&mut app);
+
+        set_content(&mut app, root, None, ContentPolicy::Detach);
+
+        assert_eq!(parent(&app, content), None);
+        assert!(app.world.get::<Children>(slot(&app, root)).is_none());
+        let data = app.world.get::<NinePatchData<()>>(root).unwrap();
+        assert!(data.content.as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn generated_children_are_not_replaced() {
+        let mut app = testing::app();
+        let root = spawn(&mut app);
+        build(&mut app);
+        // like the outline of the debug overlay
+        let overlay = app
+            .world
+            .spawn((NodeBundle::default(), GeneratedBy(root)))
+            .id();
+        let slot = slot(&app, root);
+        app.world.entity_mut(slot).add_child(overlay);
+
+        let content = app.world.spawn(NodeBundle::default()).id();
+        set_content(&mut app, root, Some(content), ContentPolicy::Despawn);
+        set_content(&mut app, root, None, ContentPolicy::Despawn);
+
+        assert!(app.world.get_entity(content).is_none());
+        assert_eq!(parent(&app, overlay), Some(slot));
+    }
+}
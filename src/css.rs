@@ -0,0 +1,570 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+
+use crate::{FillMode, NinePatchBuilder, NinePatchError};
+
+/// A value of a CSS `border-image-*` property
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BorderImageValue {
+    /// A number without unit: pixels of the texture for `slice`, multiple of the `border-width` for `width` and
+    /// `outset`
+    Number(f32),
+    /// A length in pixels
+    Px(f32),
+    /// A percentage of the size of the texture, only for `slice`
+    Percent(f32),
+    /// The size of the slice, only for `width`
+    Auto,
+}
+
+/// Typed equivalent of the CSS `border-image` properties. Sides are ordered like in CSS: top, right, bottom, left.
+///
+/// Like in CSS, widths and outsets without unit are multiples of the `border-width`, which is 0 by default.
+///
+/// ```
+/// # use bevy_ninepatch::{BorderImage, BorderImageValue, FillMode};
+/// let border_image: BorderImage = "border-image-slice: 20 30 fill; border-image-repeat: round stretch"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(
+///     border_image.slice,
+///     [
+///         BorderImageValue::Number(20.),
+///         BorderImageValue::Number(30.),
+///         BorderImageValue::Number(20.),
+///         BorderImageValue::Number(30.),
+///     ]
+/// );
+/// assert!(border_image.fill);
+/// assert_eq!(border_image.repeat, [FillMode::Round, FillMode::Stretch]);
+///
+/// // the shorthand, with widths and outsets after the slices
+/// let border_image: BorderImage = "border-width: 4px; border-image: url(border.png) 30 / 10px / 2 repeat"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(border_image.border_width, [4.; 4]);
+/// assert_eq!(border_image.slice, [BorderImageValue::Number(30.); 4]);
+/// assert_eq!(border_image.width, [BorderImageValue::Px(10.); 4]);
+/// assert_eq!(border_image.outset, [BorderImageValue::Number(2.); 4]);
+/// assert_eq!(border_image.repeat, [FillMode::RepeatCentered; 2]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BorderImage {
+    /// `border-image-slice`: where the texture is split, from its edges
+    pub slice: [BorderImageValue; 4],
+    /// `fill` keyword of `border-image-slice`: is the middle patch drawn
+    pub fill: bool,
+    /// `border-image-width`: size of the border patches when displayed
+    pub width: [BorderImageValue; 4],
+    /// `border-image-outset`: how far the border is drawn outside of the node
+    pub outset: [BorderImageValue; 4],
+    /// `border-image-repeat`: how the edges and middle fill the space horizontally, then vertically
+    pub repeat: [FillMode; 2],
+    /// `border-width`: width of the border of the node in pixels, that widths and outsets without unit multiply
+    pub border_width: [f32; 4],
+}
+
+impl Default for BorderImage {
+    fn default() -> Self {
+        // initial values of the CSS properties
+        BorderImage {
+            slice: [BorderImageValue::Percent(100.); 4],
+            fill: false,
+            width: [BorderImageValue::Number(1.); 4],
+            outset: [BorderImageValue::Number(0.); 4],
+            repeat: [FillMode::Stretch; 2],
+            // the initial `border-style` is `none`, which makes the border width 0
+            border_width: [0.; 4],
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> NinePatchError {
+    NinePatchError::InvalidBorderImage(reason.into())
+}
+
+fn value(token: &str) -> Result<BorderImageValue, NinePatchError> {
+    let number = |value: &str| {
+        value
+            .parse()
+            .map_err(|_| invalid(format!("invalid value {token}")))
+    };
+    if token == "auto" {
+        Ok(BorderImageValue::Auto)
+    } else if let Some(percent) = token.strip_suffix('%') {
+        Ok(BorderImageValue::Percent(number(percent)?))
+    } else if let Some(px) = token.strip_suffix("px") {
+        Ok(BorderImageValue::Px(number(px)?))
+    } else {
+        Ok(BorderImageValue::Number(number(token)?))
+    }
+}
+
+fn is_negative(value: &BorderImageValue) -> bool {
+    match value {
+        BorderImageValue::Number(value)
+        | BorderImageValue::Px(value)
+        | BorderImageValue::Percent(value) => *value < 0.,
+        BorderImageValue::Auto => false,
+    }
+}
+
+/// Expand one to four values to the four sides, like CSS
+fn sides(values: &[BorderImageValue]) -> Result<[BorderImageValue; 4], NinePatchError> {
+    match *values {
+        [all] => Ok([all; 4]),
+        [vertical, horizontal] => Ok([vertical, horizontal, vertical, horizontal]),
+        [top, horizontal, bottom] => Ok([top, horizontal, bottom, horizontal]),
+        [top, right, bottom, left] => Ok([top, right, bottom, left]),
+        _ => Err(invalid("expected one to four values")),
+    }
+}
+
+fn repeat(token: &str) -> Option<FillMode> {
+    match token {
+        "stretch" => Some(FillMode::Stretch),
+        "repeat" => Some(FillMode::RepeatCentered),
+        "round" => Some(FillMode::Round),
+        "space" => Some(FillMode::Space),
+        _ => None,
+    }
+}
+
+impl BorderImage {
+    fn set_slice(&mut self, tokens: &[&str]) -> Result<(), NinePatchError> {
+        self.fill = tokens.contains(&"fill");
+        let values = tokens
+            .iter()
+            .filter(|token| **token != "fill")
+            .map(|token| value(token))
+            .collect::<Result<Vec<_>, _>>()?;
+        if values
+            .iter()
+            .any(|value| matches!(value, BorderImageValue::Px(_) | BorderImageValue::Auto))
+        {
+            return Err(invalid("slices must be numbers or percentages"));
+        }
+        if values.iter().any(is_negative) {
+            return Err(invalid("slices can't be negative"));
+        }
+        self.slice = sides(&values)?;
+        Ok(())
+    }
+
+    fn set_width(&mut self, tokens: &[&str]) -> Result<(), NinePatchError> {
+        let values = tokens
+            .iter()
+            .map(|token| value(token))
+            .collect::<Result<Vec<_>, _>>()?;
+        if values
+            .iter()
+            .any(|value| matches!(value, BorderImageValue::Percent(_)))
+        {
+            return Err(invalid(
+                "widths in percentage of the node are not supported",
+            ));
+        }
+        if values.iter().any(is_negative) {
+            return Err(invalid("widths can't be negative"));
+        }
+        self.width = sides(&values)?;
+        Ok(())
+    }
+
+    fn set_outset(&mut self, tokens: &[&str]) -> Result<(), NinePatchError> {
+        let values = tokens
+            .iter()
+            .map(|token| value(token))
+            .collect::<Result<Vec<_>, _>>()?;
+        if values
+            .iter()
+            .any(|value| matches!(value, BorderImageValue::Percent(_) | BorderImageValue::Auto))
+        {
+            return Err(invalid("outsets must be numbers or lengths"));
+        }
+        if values.iter().any(is_negative) {
+            return Err(invalid("outsets can't be negative"));
+        }
+        self.outset = sides(&values)?;
+        Ok(())
+    }
+
+    fn set_border_width(&mut self, tokens: &[&str]) -> Result<(), NinePatchError> {
+        let values = tokens
+            .iter()
+            .map(|token| match *token {
+                "thin" => Ok(BorderImageValue::Px(1.)),
+                "medium" => Ok(BorderImageValue::Px(3.)),
+                "thick" => Ok(BorderImageValue::Px(5.)),
+                token => value(token),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut border_width = [0.; 4];
+        for (width, value) in border_width.iter_mut().zip(sides(&values)?) {
+            *width = match value {
+                BorderImageValue::Px(px) if px >= 0. => px,
+                BorderImageValue::Number(0.) => 0.,
+                _ => return Err(invalid("border widths must be lengths")),
+            };
+        }
+        self.border_width = border_width;
+        Ok(())
+    }
+
+    fn set_repeat(&mut self, tokens: &[&str]) -> Result<(), NinePatchError> {
+        let modes = tokens
+            .iter()
+            .map(|token| repeat(token).ok_or_else(|| invalid(format!("invalid repeat {token}"))))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.repeat = match *modes {
+            [both] => [both; 2],
+            [horizontal, vertical] => [horizontal, vertical],
+            _ => return Err(invalid("expected one or two repeat values")),
+        };
+        Ok(())
+    }
+
+    /// The `border-image` shorthand: `<source> || <slice> [ / <width>? [ / <outset> ]? ]? || <repeat>`
+    fn set_shorthand(&mut self, value: &str) -> Result<(), NinePatchError> {
+        // the source is not used, it's given with the texture of the `NinePatchData`. It's the only value with
+        // functional notation, like `url(...)` or `linear-gradient(...)`
+        let mut value = value.to_string();
+        while let Some(open) = value.find('(') {
+            let start = value[..open]
+                .rfind(|c: char| !(c.is_alphanumeric() || c == '-'))
+                .map_or(0, |start| start + 1);
+            let mut depth = 0;
+            let end = value[open..]
+                .find(|c: char| {
+                    depth += match c {
+                        '(' => 1,
+                        ')' => -1,
+                        _ => 0,
+                    };
+                    depth == 0
+                })
+                .map_or(value.len(), |end| open + end + 1);
+            value.replace_range(start..end, " ");
+        }
+        let value = value.replace('/', " / ");
+        let mut groups: Vec<Vec<&str>> = vec![vec![]];
+        let mut repeats = vec![];
+        for token in value.split_whitespace() {
+            if token == "/" {
+                groups.push(vec![]);
+            } else if repeat(token).is_some() {
+                repeats.push(token);
+            } else if token != "none" {
+                groups.last_mut().unwrap().push(token);
+            }
+        }
+        if groups.len() > 3 {
+            return Err(invalid("too many / in border-image"));
+        }
+        if !groups[0].is_empty() {
+            self.set_slice(&groups[0])?;
+        }
+        if let Some(width) = groups.get(1).filter(|width| !width.is_empty()) {
+            self.set_width(width)?;
+        }
+        if let Some(outset) = groups.get(2) {
+            self.set_outset(outset)?;
+        }
+        if !repeats.is_empty() {
+            self.set_repeat(&repeats)?;
+        }
+        Ok(())
+    }
+
+    /// Size of each slice in pixels of a texture of size `texture_size`, as top, right, bottom, left
+    pub(crate) fn slices(&self, texture_size: UVec2) -> [u32; 4] {
+        let size = texture_size.as_vec2();
+        let px = |slice: BorderImageValue, dimension: f32| {
+            match slice {
+                BorderImageValue::Percent(percent) => dimension * percent / 100.,
+                BorderImageValue::Number(px) | BorderImageValue::Px(px) => px,
+                BorderImageValue::Auto => 0.,
+            }
+            .max(0.)
+        };
+        // slices that overlap are reduced proportionally
+        let fit = |start: f32, end: f32, dimension: f32| {
+            if start + end > dimension {
+                let factor = dimension / (start + end);
+                (start * factor, end * factor)
+            } else {
+                (start, end)
+            }
+        };
+        let [top, right, bottom, left] = self.slice;
+        let (top, bottom) = fit(px(top, size.y), px(bottom, size.y), size.y);
+        let (left, right) = fit(px(left, size.x), px(right, size.x), size.x);
+        [top, right, bottom, left].map(|slice| slice.round() as u32)
+    }
+
+    /// The same border image with its horizontal and vertical axes swapped
+    pub(crate) fn transposed(self) -> Self {
+        let transpose =
+            |[top, right, bottom, left]: [BorderImageValue; 4]| [left, bottom, right, top];
+        BorderImage {
+            slice: transpose(self.slice),
+            fill: self.fill,
+            width: transpose(self.width),
+            outset: transpose(self.outset),
+            repeat: [self.repeat[1], self.repeat[0]],
+            border_width: {
+                let [top, right, bottom, left] = self.border_width;
+                [left, bottom, right, top]
+            },
+        }
+    }
+
+    /// Size of each border patch when displayed, from the slices in pixels, as top, right, bottom, left
+    pub(crate) fn widths(&self, slices: [u32; 4]) -> [f32; 4] {
+        let mut widths = [0.; 4];
+        for (((width, value), slice), border_width) in widths
+            .iter_mut()
+            .zip(self.width)
+            .zip(slices)
+            .zip(self.border_width)
+        {
+            *width = match value {
+                BorderImageValue::Auto => slice as f32,
+                BorderImageValue::Number(multiple) => border_width * multiple,
+                BorderImageValue::Px(px) => px,
+                BorderImageValue::Percent(_) => slice as f32,
+            };
+        }
+        widths
+    }
+
+    /// How far each border is drawn outside of the node, as top, right, bottom, left
+    pub(crate) fn outsets(&self) -> [f32; 4] {
+        let mut outsets = [0.; 4];
+        for ((outset, value), border_width) in
+            outsets.iter_mut().zip(self.outset).zip(self.border_width)
+        {
+            *outset = match value {
+                BorderImageValue::Number(multiple) => border_width * multiple,
+                BorderImageValue::Px(px) => px,
+                BorderImageValue::Percent(_) | BorderImageValue::Auto => 0.,
+            };
+        }
+        outsets
+    }
+}
+
+impl FromStr for BorderImage {
+    type Err = NinePatchError;
+
+    /// Parse CSS declarations of `border-image`, `border-image-slice`, `border-image-width`, `border-image-outset`,
+    /// `border-image-repeat` and `border-width`, separated by `;`. Other declarations are ignored.
+    fn from_str(css: &str) -> Result<Self, Self::Err> {
+        let mut border_image = BorderImage::default();
+        for declaration in css.split(';') {
+            let Some((property, value)) = declaration.split_once(':') else {
+                if declaration.trim().is_empty() {
+                    continue;
+                }
+                return Err(invalid(format!("invalid declaration {declaration}")));
+            };
+            let tokens: Vec<&str> = value.split_whitespace().collect();
+            match property.trim() {
+                "border-image" => border_image.set_shorthand(value)?,
+                "border-image-slice" => border_image.set_slice(&tokens)?,
+                "border-image-width" => border_image.set_width(&tokens)?,
+                "border-image-outset" => border_image.set_outset(&tokens)?,
+                "border-image-repeat" => border_image.set_repeat(&tokens)?,
+                "border-width" => border_image.set_border_width(&tokens)?,
+                _ => (),
+            }
+        }
+        Ok(border_image)
+    }
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchBuilder<T> {
+    /// Create a nine patch from a typed CSS border image, with the middle patch as the content zone `content`. The
+    /// size of the patches is computed from the size of the texture when the builder is applied.
+    pub fn from_border_image(border_image: BorderImage, content: T) -> Self {
+        let mut builder = Self::by_margins_with_content(0, 0, 0, 0, content);
+        builder.border_image = Some(border_image);
+        if !border_image.fill {
+            builder.hidden_patches.insert((1, 1));
+        }
+        builder.with_fill(border_image.repeat[0], border_image.repeat[1])
+    }
+}
+
+impl<T: Clone + Send + Sync + Default + Eq + std::hash::Hash + TypePath + 'static>
+    NinePatchBuilder<T>
+{
+    /// Create a nine patch from CSS `border-image` declarations, see [`BorderImage`]
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_ninepatch::NinePatchBuilder;
+    /// // slices in percentage of the texture size
+    /// let builder = NinePatchBuilder::<()>::from_css_border_image("border-image-slice: 10% 25% fill").unwrap();
+    /// let texture_size = UVec2::new(200, 100);
+    /// let rects = builder.resolve(texture_size);
+    /// assert_eq!(rects[0][0], URect::new(0, 0, 50, 10));
+    /// assert_eq!(rects[1][1], URect::new(50, 10, 150, 90));
+    /// assert_eq!(rects[2][2], URect::new(150, 90, 200, 100));
+    ///
+    /// // borders displayed at twice the border width
+    /// let builder = NinePatchBuilder::<()>::from_css_border_image(
+    ///     "border-width: 10px 20px; border-image-slice: 10 20; border-image-width: 2",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(builder.min_size(texture_size), Vec2::new(80., 40.));
+    /// let layout = builder.layout(texture_size, Vec2::new(300., 200.));
+    /// assert_eq!(layout[0][0], Rect::new(0., 0., 40., 20.));
+    /// assert_eq!(layout[1][1], Rect::new(40., 20., 260., 180.));
+    /// ```
+    pub fn from_css_border_image(css: &str) -> Result<Self, NinePatchError> {
+        Ok(Self::from_border_image(css.parse()?, T::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::invalid;
+    use crate::{BorderImage, BorderImageValue, FillMode, NinePatchBuilder};
+
+    use BorderImageValue::{Auto, Number, Percent, Px};
+
+    fn parse(css: &str) -> BorderImage {
+        css.parse().unwrap()
+    }
+
+    #[test]
+    fn mdn_border_image_slice() {
+        let slice = |value: &str| parse(&format!("border-image-slice: {value}"));
+        assert_eq!(slice("30%").slice, [Percent(30.); 4]);
+        assert_eq!(
+            slice("10% 30%").slice,
+            [Percent(10.), Percent(30.), Percent(10.), Percent(30.)]
+        );
+        assert_eq!(
+            slice("30 30% 45").slice,
+            [Number(30.), Percent(30.), Number(45.), Percent(30.)]
+        );
+        assert_eq!(
+            slice("7 12 14 5").slice,
+            [Number(7.), Number(12.), Number(14.), Number(5.)]
+        );
+        assert!(!slice("7 12 14 5").fill);
+        for value in ["10% fill", "fill 10%"] {
+            let border_image = slice(value);
+            assert_eq!(border_image.slice, [Percent(10.); 4]);
+            assert!(border_image.fill);
+        }
+    }
+
+    #[test]
+    fn mdn_border_image() {
+        let border_image = parse("border-image: linear-gradient(red, blue) 27");
+        assert_eq!(
+            border_image,
+            BorderImage {
+                slice: [Number(27.); 4],
+                ..default()
+            }
+        );
+
+        let border_image = parse(
+            r#"border-image: url("/images/border.png") 27 23 / 50px 30px / 10px round space"#,
+        );
+        assert_eq!(
+            border_image.slice,
+            [Number(27.), Number(23.), Number(27.), Number(23.)]
+        );
+        assert_eq!(border_image.width, [Px(50.), Px(30.), Px(50.), Px(30.)]);
+        assert_eq!(border_image.outset, [Px(10.); 4]);
+        assert_eq!(border_image.repeat, [FillMode::Round, FillMode::Space]);
+
+        let border_image =
+            parse("border-image: repeat image-set(url(border.png) 1x, url(border-2x.png) 2x) 30 fill / auto");
+        assert_eq!(border_image.slice, [Number(30.); 4]);
+        assert!(border_image.fill);
+        assert_eq!(border_image.width, [Auto; 4]);
+        assert_eq!(border_image.repeat, [FillMode::RepeatCentered; 2]);
+    }
+
+    #[test]
+    fn widths_and_outsets_multiply_the_border_width() {
+        let border_image = parse(
+            "border-width: 5px 10px; border-image: 10 20 / 2 auto 8px / 1 3px; border-image-repeat: stretch",
+        );
+        assert_eq!(border_image.border_width, [5., 10., 5., 10.]);
+        let texture_size = UVec2::new(100, 50);
+        let slices = border_image.slices(texture_size);
+        assert_eq!(slices, [10, 20, 10, 20]);
+        assert_eq!(border_image.widths(slices), [10., 20., 8., 20.]);
+        assert_eq!(border_image.outsets(), [5., 3., 5., 3.]);
+
+        let builder = NinePatchBuilder::<()>::from_border_image(border_image, ());
+        let layout = builder.layout(texture_size, Vec2::new(200., 100.));
+        assert_eq!(layout[0][0], Rect::new(0., 0., 20., 10.));
+        assert_eq!(layout[1][1], Rect::new(20., 10., 180., 92.));
+        assert_eq!(layout[2][2], Rect::new(180., 92., 200., 100.));
+        assert_eq!(builder.min_size(texture_size), Vec2::new(40., 18.));
+
+        // without a border width, the initial width of 1 hides the borders
+        let border_image = parse("border-image-slice: 10");
+        assert_eq!(
+            border_image.widths(border_image.slices(texture_size)),
+            [0.; 4]
+        );
+    }
+
+    #[test]
+    fn invalid_declarations() {
+        for (css, error) in [
+            (
+                "border-image-slice 10",
+                "invalid declaration border-image-slice 10",
+            ),
+            ("border-image-slice: abc", "invalid value abc"),
+            (
+                "border-image-slice: 10px",
+                "slices must be numbers or percentages",
+            ),
+            (
+                "border-image-slice: 1 2 3 4 5",
+                "expected one to four values",
+            ),
+            ("border-image-slice: -1", "slices can't be negative"),
+            (
+                "border-image-width: 10%",
+                "widths in percentage of the node are not supported",
+            ),
+            ("border-image-width: -2px", "widths can't be negative"),
+            (
+                "border-image-outset: auto",
+                "outsets must be numbers or lengths",
+            ),
+            ("border-image-outset: -1", "outsets can't be negative"),
+            ("border-image-repeat: mirror", "invalid repeat mirror"),
+            (
+                "border-image-repeat: round space stretch",
+                "expected one or two repeat values",
+            ),
+            ("border-image: 10 / 2 / 3 / 4", "too many / in border-image"),
+            (
+                "border-image: url(border.png) 10 / 1rem",
+                "invalid value 1rem",
+            ),
+            ("border-width: 10%", "border widths must be lengths"),
+        ] {
+            assert_eq!(css.parse::<BorderImage>(), Err(invalid(error)), "{css}");
+        }
+    }
+}
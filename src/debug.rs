@@ -0,0 +1,204 @@
+use bevy::{prelude::*, ui::FocusPolicy};
+
+use crate::{
+    ninepatch::{BorderSize, GeneratedBy, NinePatchCell, NinePatchContent},
+    NinePatchData,
+};
+
+/// Color of the outline of patches with a fixed size
+const FIXED_COLOR: Color = Color::RED;
+/// Color of the outline of patches that can grow
+const GROWING_COLOR: Color = Color::GREEN;
+/// Color of the outline of content zones
+const CONTENT_COLOR: Color = Color::CYAN;
+
+/// Resource to toggle the debug overlay at runtime
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct NinePatchDebug {
+    /// Is the debug overlay displayed
+    pub enabled: bool,
+}
+
+impl Default for NinePatchDebug {
+    fn default() -> Self {
+        NinePatchDebug { enabled: true }
+    }
+}
+
+/// Plugin that outlines each patch and content zone of 9-Patch UI elements, with their position in the grid and the
+/// name of the content zones. Patches with a fixed size are outlined in red, patches that can grow in green, and
+/// content zones in cyan.
+#[derive(Debug, Clone, Copy)]
+pub struct NinePatchDebugPlugin<T: Clone + Send + Sync + 'static = ()> {
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for NinePatchDebugPlugin<T> {
+    fn default() -> Self {
+        NinePatchDebugPlugin {
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + std::fmt::Debug + 'static> Plugin
+    for NinePatchDebugPlugin<T>
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NinePatchDebug>()
+            .add_systems(Update, update_debug_overlays::<T>);
+    }
+}
+
+/// Mark the nodes of the debug overlay
+#[derive(Debug, Clone, Copy, Component)]
+struct NinePatchDebugOverlay;
+
+fn spawn_overlay(
+    commands: &mut Commands,
+    root: Entity,
+    parent: Entity,
+    color: Color,
+    label: String,
+    label_at_bottom: bool,
+) {
+    let (top, bottom) = if label_at_bottom {
+        (Val::Auto, Val::Px(1.))
+    } else {
+        (Val::Px(1.), Val::Auto)
+    };
+    let overlay = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    border: UiRect::all(Val::Px(1.)),
+                    ..default()
+                },
+                border_color: BorderColor(color),
+                background_color: BackgroundColor(Color::NONE),
+                focus_policy: FocusPolicy::Pass,
+                ..default()
+            },
+            NinePatchDebugOverlay,
+            // so that it's not mistaken for content
+            GeneratedBy(root),
+        ))
+        .with_children(|overlay| {
+            overlay.spawn(
+                TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font_size: 10.,
+                        color,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(1.),
+                    top,
+                    bottom,
+                    ..default()
+                }),
+            );
+        })
+        .id();
+    commands.entity(parent).add_child(overlay);
+}
+
+#[allow(clippy::type_complexity)]
+fn update_debug_overlays<
+    T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + std::fmt::Debug + 'static,
+>(
+    mut commands: Commands,
+    debug: Res<NinePatchDebug>,
+    overlays_query: Query<Entity, With<NinePatchDebugOverlay>>,
+    cells_query: Query<(Entity, Ref<NinePatchCell>, Option<&BorderSize>)>,
+    contents_query: Query<(Entity, Ref<NinePatchContent<T>>)>,
+    roots_query: Query<(), With<NinePatchData<T>>>,
+) {
+    let rebuild = debug.is_changed();
+    if rebuild {
+        for overlay in overlays_query.iter() {
+            commands.entity(overlay).despawn_recursive();
+        }
+    }
+    if !debug.enabled {
+        return;
+    }
+
+    for (entity, cell, border_size) in cells_query.iter() {
+        if !(rebuild || cell.is_added()) || !roots_query.contains(cell.root) {
+            continue;
+        }
+        let fixed = border_size.is_some_and(|size| size.width.is_some() && size.height.is_some());
+        spawn_overlay(
+            &mut commands,
+            cell.root,
+            entity,
+            if fixed { FIXED_COLOR } else { GROWING_COLOR },
+            format!("{},{}", cell.row, cell.column),
+            false,
+        );
+    }
+    for (entity, content) in contents_query.iter() {
+        if !(rebuild || content.is_added()) || !roots_query.contains(content.parent) {
+            continue;
+        }
+        spawn_overlay(
+            &mut commands,
+            content.parent,
+            entity,
+            CONTENT_COLOR,
+            format!("{:?}", content.content),
+            true,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::NinePatchDebugOverlay;
+    use crate::{testing, NinePatchBuilder, NinePatchData, NinePatchDebug, NinePatchDebugPlugin};
+
+    fn overlays(app: &mut App) -> usize {
+        let mut overlays_query = app
+            .world
+            .query_filtered::<(), With<NinePatchDebugOverlay>>();
+        overlays_query.iter(&app.world).count()
+    }
+
+    #[test]
+    fn overlay_is_toggled_and_cleaned_up() {
+        let mut app = testing::app();
+        app.add_plugins(NinePatchDebugPlugin::<()>::default());
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(10, 10, 10, 10),
+            UVec2::new(30, 30),
+            Vec2::new(100., 100.),
+        );
+        for _ in 0..3 {
+            app.update();
+        }
+        // nine patches and the content zone
+        assert_eq!(overlays(&mut app), 10);
+
+        app.world.resource_mut::<NinePatchDebug>().enabled = false;
+        app.update();
+        assert_eq!(overlays(&mut app), 0);
+
+        app.world.resource_mut::<NinePatchDebug>().enabled = true;
+        app.update();
+        assert_eq!(overlays(&mut app), 10);
+
+        app.world.entity_mut(root).remove::<NinePatchData<()>>();
+        app.update();
+        assert_eq!(overlays(&mut app), 0);
+    }
+}
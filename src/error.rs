@@ -0,0 +1,134 @@
+use std::ops::Range;
+
+use bevy::math::URect;
+
+/// Errors found when checking or importing a nine patch definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NinePatchError {
+    /// The definition has no patches, or a row without patches
+    Empty,
+    /// The region of the texture holding the patches is outside of the texture
+    RegionOutOfTexture(URect),
+    /// A patch is outside of the texture
+    PatchOutOfTexture {
+        /// Row of the patch
+        row: usize,
+        /// Position of the patch in its row
+        column: usize,
+    },
+    /// A patch doesn't have the same height as the first patch of its row
+    UnevenRow {
+        /// Row of the patch
+        row: usize,
+        /// Position of the patch in its row
+        column: usize,
+        /// Height of the first patch of the row
+        expected: u32,
+        /// Height of the patch
+        found: u32,
+    },
+    /// A patch isn't rendered at the same height as the first patch of its row
+    MisalignedRow {
+        /// Row of the patch
+        row: usize,
+        /// Position of the patch in its row
+        column: usize,
+    },
+    /// In a grid of columns, a patch doesn't have the same width as the first patch of its column
+    UnevenColumn {
+        /// Column of the patch
+        column: usize,
+        /// Position of the patch in its column
+        row: usize,
+        /// Width of the first patch of the column
+        expected: u32,
+        /// Width of the patch
+        found: u32,
+    },
+    /// In a grid of columns, a patch isn't rendered at the same width as the first patch of its column
+    MisalignedColumn {
+        /// Column of the patch
+        column: usize,
+        /// Position of the patch in its column
+        row: usize,
+    },
+    /// A content span covers patches that don't exist
+    SpanOutOfGrid {
+        /// Rows covered by the span
+        rows: Range<usize>,
+        /// Patches covered by the span in each row
+        columns: Range<usize>,
+    },
+    /// The guides of an Android `.9.png` image are invalid
+    InvalidGuides(String),
+    /// An Aseprite file or JSON export can't be read
+    InvalidAseprite(String),
+    /// A Godot resource can't be read
+    InvalidGodotResource(String),
+    /// A Unity `.meta` file can't be read
+    InvalidUnityMeta(String),
+    /// A LibGDX texture atlas can't be read
+    InvalidLibGdxAtlas(String),
+    /// A CSS border image can't be read or applied
+    InvalidBorderImage(String),
+}
+
+impl std::fmt::Display for NinePatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NinePatchError::Empty => write!(f, "there are no patches, or a row has no patches"),
+            NinePatchError::RegionOutOfTexture(region) => write!(
+                f,
+                "region from {},{} to {},{} is outside of the texture",
+                region.min.x, region.min.y, region.max.x, region.max.y
+            ),
+            NinePatchError::PatchOutOfTexture { row, column } => {
+                write!(f, "patch {row},{column} is outside of the texture")
+            }
+            NinePatchError::UnevenRow {
+                row,
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "patch {row},{column} is {found}px high, but the first patch of its row is {expected}px high"
+            ),
+            NinePatchError::MisalignedRow { row, column } => write!(
+                f,
+                "patch {row},{column} isn't rendered at the same height as the first patch of its row"
+            ),
+            NinePatchError::UnevenColumn {
+                column,
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "patch {row},{column} is {found}px wide, but the first patch of its column is {expected}px wide"
+            ),
+            NinePatchError::MisalignedColumn { column, row } => write!(
+                f,
+                "patch {row},{column} isn't rendered at the same width as the first patch of its column"
+            ),
+            NinePatchError::SpanOutOfGrid { rows, columns } => write!(
+                f,
+                "content span covering rows {rows:?} and columns {columns:?} is outside of the patches"
+            ),
+            NinePatchError::InvalidGuides(reason) => write!(f, "invalid .9.png guides: {reason}"),
+            NinePatchError::InvalidAseprite(reason) => write!(f, "invalid Aseprite file: {reason}"),
+            NinePatchError::InvalidGodotResource(reason) => {
+                write!(f, "invalid Godot resource: {reason}")
+            }
+            NinePatchError::InvalidUnityMeta(reason) => write!(f, "invalid Unity .meta: {reason}"),
+            NinePatchError::InvalidLibGdxAtlas(reason) => {
+                write!(f, "invalid LibGDX atlas: {reason}")
+            }
+            NinePatchError::InvalidBorderImage(reason) => {
+                write!(f, "invalid border image: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NinePatchError {}
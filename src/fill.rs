@@ -0,0 +1,268 @@
+use bevy::{prelude::*, ui::FocusPolicy};
+
+use crate::{
+    ninepatch::{GeneratedBy, PatchLayer},
+    plugin::layer_tint,
+    NinePatchTint,
+};
+
+/// Above this number of tiles in a patch, it is stretched instead
+const MAX_TILES: usize = 1024;
+
+/// How a patch fills the space along an axis on which it grows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillMode {
+    /// Stretch the patch to fill the space
+    #[default]
+    Stretch,
+    /// Repeat the patch from its start, the last repetition is cut if it doesn't fit
+    Repeat,
+    /// Repeat the patch from the center, repetitions at both ends are cut if they don't fit. This is CSS `repeat`
+    RepeatCentered,
+    /// Repeat the patch a whole number of times, scaled to fill the space
+    Round,
+    /// Repeat the patch as many times as it fits, with the remaining space spread between repetitions
+    Space,
+}
+
+impl FillMode {
+    /// Start of each tile and their size, to fill `available` space with tiles of size `tile`
+    fn tiles(self, available: f32, tile: f32) -> (Vec<f32>, f32) {
+        let from = |start: f32, step: f32| {
+            std::iter::successors(Some(start), move |position| Some(position + step))
+                .take_while(move |position| *position < available)
+                .collect()
+        };
+        match self {
+            FillMode::Stretch => (vec![0.], available),
+            FillMode::Repeat => (from(0., tile), tile),
+            FillMode::RepeatCentered => {
+                // one tile is centered, the others are placed around it
+                let start = ((available - tile) / 2.).rem_euclid(tile);
+                let start = if start > 0. { start - tile } else { start };
+                (from(start, tile), tile)
+            }
+            FillMode::Round => {
+                let size = available / (available / tile).round().max(1.);
+                (from(0., size), size)
+            }
+            FillMode::Space => {
+                let count = (available / tile).floor();
+                let gap = (available - count * tile) / (count + 1.);
+                let positions = (0..count as usize)
+                    .map(|i| gap + i as f32 * (tile + gap))
+                    .collect();
+                (positions, tile)
+            }
+        }
+    }
+}
+
+/// A patch drawn with tiles of its texture instead of stretching it
+#[derive(Debug, Clone, Component)]
+pub(crate) struct TiledPatch {
+    pub(crate) texture: Handle<Image>,
+    /// Size of the patch in the texture
+    pub(crate) size: Vec2,
+    /// Fill mode on each axis, `FillMode::Stretch` on axes where the patch doesn't grow
+    pub(crate) horizontal: FillMode,
+    pub(crate) vertical: FillMode,
+    pub(crate) grows: BVec2,
+    /// Node holding the tiles currently displayed
+    pub(crate) tiles: Option<Entity>,
+}
+
+impl TiledPatch {
+    /// Position and size of each tile, in a patch of size `size`
+    fn tiles(&self, size: Vec2) -> Vec<Rect> {
+        // tiles keep their aspect ratio when the patch is scaled on the axis where it doesn't grow
+        let scale = if !self.grows.x {
+            size.x / self.size.x
+        } else if !self.grows.y {
+            size.y / self.size.y
+        } else {
+            1.
+        };
+        let (columns, width) = self.horizontal.tiles(size.x, self.size.x * scale);
+        let (rows, height) = self.vertical.tiles(size.y, self.size.y * scale);
+        rows.iter()
+            .flat_map(|y| {
+                columns
+                    .iter()
+                    .map(move |x| Rect::new(*x, *y, x + width, y + height))
+            })
+            .collect()
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn tile_patches(
+    mut commands: Commands,
+    mut patches_query: Query<
+        (
+            Entity,
+            &Node,
+            &mut TiledPatch,
+            &GeneratedBy,
+            Option<&PatchLayer>,
+        ),
+        Or<(Changed<Node>, Changed<TiledPatch>)>,
+    >,
+    children_query: Query<&Children>,
+    mut tiles_query: Query<(&mut Style, &mut UiImage), Without<TiledPatch>>,
+    tints_query: Query<&NinePatchTint>,
+) {
+    for (entity, node, mut tiled, generated_by, layer) in patches_query.iter_mut() {
+        let size = node.size();
+        let mut tiles = if size.cmple(Vec2::ZERO).any() || tiled.size.cmple(Vec2::ZERO).any() {
+            vec![]
+        } else {
+            tiled.tiles(size)
+        };
+        if tiles.len() > MAX_TILES {
+            warn!(
+                "a patch would need {} tiles, it is stretched instead",
+                tiles.len()
+            );
+            tiles = vec![Rect::from_corners(Vec2::ZERO, size)];
+        }
+        // tiles are placed in percentages of the patch, so they follow it during the layout when it is resized,
+        // until they are computed again for its new size
+        let percent = |value: f32, total: f32| Val::Percent(value / total * 100.);
+        let styles = tiles.iter().map(|tile| Style {
+            position_type: PositionType::Absolute,
+            left: percent(tile.min.x, size.x),
+            top: percent(tile.min.y, size.y),
+            width: percent(tile.width(), size.x),
+            height: percent(tile.height(), size.y),
+            ..default()
+        });
+
+        let root = generated_by.0;
+        let container = match tiled.tiles {
+            Some(container) => container,
+            None => {
+                let container = commands
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Percent(100.),
+                                height: Val::Percent(100.),
+                                overflow: Overflow::clip(),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(Color::NONE),
+                            focus_policy: FocusPolicy::Pass,
+                            ..default()
+                        },
+                        GeneratedBy(root),
+                    ))
+                    .id();
+                // below the content of the patch
+                commands.entity(entity).insert_children(0, &[container]);
+                // not a change of the patch, which would tile it again
+                tiled.bypass_change_detection().tiles = Some(container);
+                container
+            }
+        };
+
+        // existing tiles are moved, and only the difference is spawned or despawned
+        let existing = children_query
+            .get(container)
+            .map(|children| children.to_vec())
+            .unwrap_or_default();
+        let color = layer_tint(
+            tints_query.get(root).map_or(Color::WHITE, |tint| tint.0),
+            layer,
+        );
+        for (index, style) in styles.enumerate() {
+            if let Some(tile) = existing.get(index) {
+                if let Ok((mut tile_style, mut image)) = tiles_query.get_mut(*tile) {
+                    tile_style.set_if_neq(style);
+                    if image.texture.id() != tiled.texture.id() {
+                        image.texture = tiled.texture.clone_weak();
+                    }
+                }
+                continue;
+            }
+            let mut tile = commands.spawn((
+                ImageBundle {
+                    image: UiImage {
+                        texture: tiled.texture.clone_weak(),
+                        ..default()
+                    },
+                    style,
+                    background_color: BackgroundColor(color),
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                },
+                GeneratedBy(root),
+            ));
+            if let Some(layer) = layer {
+                tile.insert(*layer);
+            }
+            let tile = tile.id();
+            commands.entity(container).add_child(tile);
+        }
+        for tile in existing.iter().skip(tiles.len()) {
+            commands.entity(*tile).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::TiledPatch;
+    use crate::{testing, FillMode, NinePatchBuilder, NinePatchCell};
+
+    fn middle_tiles(app: &mut App) -> Vec<Entity> {
+        let container = app
+            .world
+            .query::<(&NinePatchCell, &TiledPatch)>()
+            .iter(&app.world)
+            .find(|(cell, _)| cell.row == 1 && cell.column == 1)
+            .and_then(|(_, tiled)| tiled.tiles)
+            .unwrap();
+        app.world
+            .get::<Children>(container)
+            .map(|children| children.to_vec())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn tiles_are_kept_when_resized() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(10, 10, 10, 10)
+                .with_fill(FillMode::Repeat, FillMode::Repeat),
+            UVec2::new(30, 30),
+            Vec2::new(100., 60.),
+        );
+        for _ in 0..3 {
+            app.update();
+        }
+        // the middle patch is 80x40, with tiles of 10x10
+        let tiles = middle_tiles(&mut app);
+        assert_eq!(tiles.len(), 32);
+
+        app.world.get_mut::<Style>(root).unwrap().width = Val::Px(120.);
+        app.update();
+        // tiles are still laid out in the frame of the resize
+        for tile in &tiles {
+            let node = app.world.get::<Node>(*tile).unwrap();
+            assert!(node.size().cmpgt(Vec2::ZERO).all());
+        }
+
+        app.update();
+        let resized = middle_tiles(&mut app);
+        assert_eq!(resized.len(), 40);
+        assert_eq!(resized[..32], tiles[..]);
+        let node = app.world.get::<Node>(resized[39]).unwrap();
+        assert_eq!(node.size(), Vec2::new(10., 10.));
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    ecs::component::Tick,
+    prelude::*,
+    ui::{CalculatedClip, FocusPolicy},
+    window::PrimaryWindow,
+};
+
+use crate::{
+    fill::TiledPatch,
+    ninepatch::{GeneratedBy, HiddenPatch},
+    NinePatchCell,
+};
+
+/// How a 9-Patch UI element reacts to the pointer, added on the entity holding the `NinePatchData`. Without it, the
+/// element and all its patches let the pointer through to what is behind them.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct NinePatchFocus {
+    /// Focus policy of the entity holding the `NinePatchData`. `FocusPolicy::Block` stops the pointer from reaching
+    /// nodes behind the element
+    pub root: FocusPolicy,
+    /// Focus policy of each patch, the entities with a `NinePatchCell`. `FocusPolicy::Block` also stops the pointer
+    /// from reaching the entity holding the `NinePatchData`
+    pub patches: FocusPolicy,
+    /// Ignore the pointer over fully transparent pixels of the patches: the element lets it through, and the
+    /// `Interaction` of the entity holding the `NinePatchData` stays `None`
+    pub alpha_hit_test: bool,
+}
+
+impl Default for NinePatchFocus {
+    fn default() -> Self {
+        NinePatchFocus {
+            root: FocusPolicy::Pass,
+            patches: FocusPolicy::Pass,
+            alpha_hit_test: false,
+        }
+    }
+}
+
+impl NinePatchFocus {
+    /// The element stops the pointer from reaching nodes behind it
+    pub fn blocking() -> Self {
+        NinePatchFocus {
+            root: FocusPolicy::Block,
+            ..default()
+        }
+    }
+
+    /// Ignore the pointer over fully transparent pixels of the patches
+    pub fn with_alpha_hit_test(mut self) -> Self {
+        self.alpha_hit_test = true;
+        self
+    }
+}
+
+/// State of the pointer over a 9-Patch UI element with a `NinePatchFocus`, kept out of it so that it is only
+/// changed by the user
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct FocusState {
+    /// Is the pointer over a transparent pixel of the patches
+    transparent: bool,
+    /// `Interaction` of the element before the focus of the UI is computed, and when it last changed
+    interaction: Option<(Interaction, Tick)>,
+}
+
+/// Alpha of the pixel of `texture` at `position`, from (0, 0) at its top left corner to (1, 1) at its bottom right
+fn alpha_at(texture: &Image, position: Vec2) -> u8 {
+    let size = texture.texture_descriptor.size;
+    let size = UVec2::new(size.width, size.height);
+    let pixel = (position * size.as_vec2())
+        .as_uvec2()
+        .min(size.saturating_sub(UVec2::ONE));
+    // split patches are always RGBA with 8 bits per channel
+    texture
+        .data
+        .get(((pixel.y * size.x + pixel.x) * 4 + 3) as usize)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Position of the pointer, in logical UI coordinates
+fn pointer_position(
+    window: Option<&Window>,
+    touches: &Touches,
+    ui_scale: &UiScale,
+) -> Option<Vec2> {
+    window
+        .and_then(Window::cursor_position)
+        .or_else(|| touches.first_pressed_position())
+        .map(|position| position / ui_scale.0 as f32)
+}
+
+/// Update the focus policy of elements and patches, before the focus of the UI is computed
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn focus_ninepatches(
+    mut commands: Commands,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    touches: Res<Touches>,
+    ui_scale: Res<UiScale>,
+    textures: Res<Assets<Image>>,
+    mut roots_query: Query<
+        (
+            Entity,
+            &NinePatchFocus,
+            Option<&mut FocusState>,
+            Option<Ref<Interaction>>,
+            &Node,
+            &GlobalTransform,
+            &mut FocusPolicy,
+        ),
+        Without<NinePatchCell>,
+    >,
+    mut cells_query: Query<(&NinePatchCell, &mut FocusPolicy)>,
+    images_query: Query<
+        (
+            &GeneratedBy,
+            &Node,
+            &GlobalTransform,
+            &UiImage,
+            Option<&CalculatedClip>,
+        ),
+        (Without<HiddenPatch>, Without<TiledPatch>),
+    >,
+) {
+    if roots_query.is_empty() {
+        return;
+    }
+    let pointer = pointer_position(windows.get_single().ok(), &touches, &ui_scale);
+
+    // elements with a visible pixel under the pointer, in any of their patches or tiles
+    let mut opaque = HashSet::new();
+    let alpha_tested = roots_query
+        .iter()
+        .any(|(_, focus, ..)| focus.alpha_hit_test);
+    if let Some(pointer) = pointer.filter(|_| alpha_tested) {
+        for (generated_by, node, transform, image, clip) in images_query.iter() {
+            if opaque.contains(&generated_by.0) {
+                continue;
+            }
+            let rect = node.logical_rect(transform);
+            let visible = clip.map_or(rect, |clip| rect.intersect(clip.clip));
+            if rect.is_empty() || !visible.contains(pointer) {
+                continue;
+            }
+            let hit = textures
+                .get(&image.texture)
+                .is_some_and(|texture| alpha_at(texture, (pointer - rect.min) / rect.size()) > 0);
+            if hit {
+                opaque.insert(generated_by.0);
+            }
+        }
+    }
+
+    let mut patch_policies = HashMap::new();
+    for (entity, focus, state, interaction, node, transform, mut policy) in roots_query.iter_mut() {
+        let transparent = focus.alpha_hit_test
+            && !(opaque.contains(&entity)
+                && pointer.is_some_and(|pointer| node.logical_rect(transform).contains(pointer)));
+        let new_state = FocusState {
+            transparent,
+            interaction: interaction.map(|interaction| (*interaction, interaction.last_changed())),
+        };
+        match state {
+            Some(mut state) => *state = new_state,
+            None => {
+                commands.entity(entity).insert(new_state);
+            }
+        }
+        let (root, patches) = if transparent {
+            (FocusPolicy::Pass, FocusPolicy::Pass)
+        } else {
+            (focus.root, focus.patches)
+        };
+        policy.set_if_neq(root);
+        patch_policies.insert(entity, patches);
+    }
+    for (cell, mut policy) in cells_query.iter_mut() {
+        if let Some(patches) = patch_policies.get(&cell.root) {
+            policy.set_if_neq(*patches);
+        }
+    }
+}
+
+/// Reset the interaction of elements with the pointer over a transparent pixel, after the focus of the UI is computed
+pub(crate) fn ignore_transparent_pixels(
+    mut roots_query: Query<(&FocusState, &mut Interaction), With<NinePatchFocus>>,
+) {
+    for (state, mut interaction) in roots_query.iter_mut() {
+        if !state.transparent {
+            continue;
+        }
+        match state.interaction {
+            // undo the change made by the focus of the UI, so that the interaction doesn't change every frame
+            Some((Interaction::None, changed)) if *interaction != Interaction::None => {
+                *interaction.bypass_change_detection() = Interaction::None;
+                interaction.set_last_changed(changed);
+            }
+            _ => {
+                interaction.set_if_neq(Interaction::None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{testing, NinePatchBuilder, NinePatchData, NinePatchFocus};
+
+    /// Element of 100x100 at (100, 100), from a texture of 30x30 with a transparent top left corner
+    fn spawn(app: &mut App, builder: NinePatchBuilder) -> Entity {
+        testing::camera(app);
+        let root = testing::spawn(app, builder, UVec2::new(30, 30), Vec2::new(100., 100.));
+        let texture = app
+            .world
+            .get::<NinePatchData<()>>(root)
+            .unwrap()
+            .texture
+            .clone();
+        let mut textures = app.world.resource_mut::<Assets<Image>>();
+        let image = textures.get_mut(&texture).unwrap();
+        for y in 0..10 {
+            for x in 0..10 {
+                image.data[(y * 30 + x) * 4 + 3] = 0;
+            }
+        }
+        app.world.get_mut::<Style>(root).unwrap().margin = UiRect::all(Val::Px(100.));
+        app.world.entity_mut(root).insert((
+            Interaction::None,
+            NinePatchFocus::default().with_alpha_hit_test(),
+        ));
+        for _ in 0..3 {
+            app.update();
+        }
+        root
+    }
+
+    fn interaction_at(app: &mut App, root: Entity, cursor: Vec2) -> Interaction {
+        testing::move_cursor(app, cursor);
+        app.update();
+        *app.world.get::<Interaction>(root).unwrap()
+    }
+
+    #[test]
+    fn transparent_pixels_are_ignored() {
+        let mut app = testing::app();
+        let root = spawn(&mut app, NinePatchBuilder::by_margins(10, 10, 10, 10));
+
+        assert_eq!(
+            interaction_at(&mut app, root, Vec2::new(105., 105.)),
+            Interaction::None
+        );
+        assert_eq!(
+            interaction_at(&mut app, root, Vec2::new(195., 105.)),
+            Interaction::Hovered
+        );
+        assert_eq!(
+            interaction_at(&mut app, root, Vec2::new(105., 105.)),
+            Interaction::None
+        );
+        assert_eq!(
+            interaction_at(&mut app, root, Vec2::new(150., 150.)),
+            Interaction::Hovered
+        );
+    }
+
+    #[test]
+    fn pointer_is_confined_to_the_node() {
+        let mut app = testing::app();
+        let builder =
+            NinePatchBuilder::by_margins(10, 10, 10, 10).with_outset(UiRect::all(Val::Px(20.)));
+        let root = spawn(&mut app, builder);
+
+        // over an opaque pixel of the outset, outside of the node
+        assert_eq!(
+            interaction_at(&mut app, root, Vec2::new(150., 90.)),
+            Interaction::None
+        );
+        assert_eq!(
+            interaction_at(&mut app, root, Vec2::new(210., 150.)),
+            Interaction::None
+        );
+        // the outset moved the transparent corner out of the node
+        assert_eq!(
+            interaction_at(&mut app, root, Vec2::new(105., 105.)),
+            Interaction::Hovered
+        );
+        assert_eq!(
+            interaction_at(&mut app, root, Vec2::new(85., 85.)),
+            Interaction::None
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+
+use crate::{
+    plugin::init_string_builders, ContentLayout, FillMode, NinePatchBuilder, NinePatchError,
+};
+
+/// Name of the content zone of a `NinePatchBuilder` read from a Godot `StyleBoxTexture`
+pub const GODOT_CONTENT: &str = "content";
+
+/// Label of the `NinePatchBuilder` in an asset loaded from a Godot `.tres` file
+pub const GODOT_NINE_PATCH: &str = "nine_patch";
+
+/// Nine patch read from a Godot `StyleBoxTexture` resource
+#[derive(Debug, Clone, TypePath, Asset)]
+pub struct GodotStyleBox {
+    /// Texture of the style box
+    pub texture: Handle<Image>,
+    /// Nine patch of the style box, with its content zone [`GODOT_CONTENT`]
+    pub nine_patch: Handle<NinePatchBuilder<String>>,
+    /// `modulate_color` of the style box, to use with [`NinePatchTint`](crate::NinePatchTint)
+    pub tint: Color,
+    /// `expand_margin_*` of the style box, in pixels: how far the style box is drawn outside of its node. It is also
+    /// the outset of `nine_patch`
+    pub expand_margin: UiRect,
+}
+
+/// Plugin to load Godot `StyleBoxTexture` resources (`.tres`) as [`GodotStyleBox`]. Texture paths starting with
+/// `res://` are relative to the assets folder, other paths are relative to the `.tres` file.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NinePatchGodotPlugin;
+
+impl Plugin for NinePatchGodotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<GodotStyleBox>()
+            .init_asset_loader::<GodotStyleBoxLoader>();
+    }
+
+    fn finish(&self, app: &mut App) {
+        init_string_builders(app);
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> NinePatchError {
+    NinePatchError::InvalidGodotResource(reason.into())
+}
+
+/// Properties of a `StyleBoxTexture`, with sides ordered left, top, right, bottom
+#[derive(Debug, Clone, PartialEq)]
+struct StyleBoxTexture {
+    texture: String,
+    region: Option<URect>,
+    texture_margin: [f32; 4],
+    expand_margin: [f32; 4],
+    content_margin: [f32; 4],
+    horizontal_fill: FillMode,
+    vertical_fill: FillMode,
+    modulate: Color,
+}
+
+/// `key=value` attributes of a section header like `[ext_resource type="Texture2D" path="res://a.png" id="1"]`
+fn attributes(header: &str) -> HashMap<&str, &str> {
+    let mut attributes = HashMap::new();
+    let mut rest = header;
+    while let Some(equal) = rest.find('=') {
+        let key = rest[..equal].split_whitespace().last().unwrap_or_default();
+        let value = rest[equal + 1..].trim_start();
+        let (value, next) = match value.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                (&quoted[..end], &quoted[(end + 1).min(quoted.len())..])
+            }
+            None => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            }
+        };
+        attributes.insert(key, value);
+        rest = next;
+    }
+    attributes
+}
+
+/// Arguments of a constructor value like `Color(1, 1, 1, 1)`
+fn arguments(value: &str, constructor: &str) -> Result<Vec<f32>, NinePatchError> {
+    value
+        .strip_prefix(constructor)
+        .and_then(|value| value.trim().strip_prefix('('))
+        .and_then(|value| value.strip_suffix(')'))
+        .ok_or_else(|| invalid(format!("expected a {constructor}, found {value}")))?
+        .split(',')
+        .map(|argument| {
+            argument
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("invalid {constructor} {value}")))
+        })
+        .collect()
+}
+
+fn number(key: &str, value: &str) -> Result<f32, NinePatchError> {
+    value
+        .parse()
+        .map_err(|_| invalid(format!("{key} should be a number, found {value}")))
+}
+
+fn axis_stretch(key: &str, value: &str) -> Result<FillMode, NinePatchError> {
+    match value {
+        "0" => Ok(FillMode::Stretch),
+        "1" => Ok(FillMode::Repeat),
+        "2" => Ok(FillMode::Round),
+        _ => Err(invalid(format!("unknown {key} {value}"))),
+    }
+}
+
+/// Read a `StyleBoxTexture` from a Godot 3 or Godot 4 `.tres` resource
+fn read_style_box(tres: &str) -> Result<StyleBoxTexture, NinePatchError> {
+    let mut style_box = StyleBoxTexture {
+        texture: String::new(),
+        region: None,
+        texture_margin: [0.; 4],
+        expand_margin: [0.; 4],
+        content_margin: [-1.; 4],
+        horizontal_fill: FillMode::Stretch,
+        vertical_fill: FillMode::Stretch,
+        modulate: Color::WHITE,
+    };
+    let mut external_resources = HashMap::new();
+    let mut texture_id = None;
+    let mut in_resource = false;
+    for line in tres.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let (kind, attributes_text) = header.split_once(' ').unwrap_or((header, ""));
+            let attributes = attributes(attributes_text);
+            match kind {
+                "gd_resource" if attributes.get("type") != Some(&"StyleBoxTexture") => {
+                    return Err(invalid(format!(
+                        "expected a StyleBoxTexture resource, found {}",
+                        attributes.get("type").unwrap_or(&"nothing")
+                    )));
+                }
+                "ext_resource" => {
+                    if let (Some(id), Some(path)) = (attributes.get("id"), attributes.get("path")) {
+                        external_resources.insert(id.to_string(), path.to_string());
+                    }
+                }
+                _ => (),
+            }
+            in_resource = kind == "resource";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if !in_resource {
+            continue;
+        }
+        let (key, value) = (key.trim(), value.trim());
+        // sides are left, top, right, bottom
+        let side = |name: &str| match name {
+            "left" => Some(0),
+            "top" => Some(1),
+            "right" => Some(2),
+            "bottom" => Some(3),
+            _ => None,
+        };
+        match key.rsplit_once('_') {
+            // `margin_*` in Godot 3, `texture_margin_*` in Godot 4
+            Some(("margin" | "texture_margin", name)) if side(name).is_some() => {
+                style_box.texture_margin[side(name).unwrap()] = number(key, value)?;
+            }
+            Some(("expand_margin", name)) if side(name).is_some() => {
+                style_box.expand_margin[side(name).unwrap()] = number(key, value)?;
+            }
+            Some(("content_margin", name)) if side(name).is_some() => {
+                style_box.content_margin[side(name).unwrap()] = number(key, value)?;
+            }
+            _ => match key {
+                "texture" => {
+                    texture_id = Some(
+                        value
+                            .strip_prefix("ExtResource")
+                            .map(|id| id.trim_matches(|c: char| "( )\"".contains(c)))
+                            .ok_or_else(|| invalid("the texture must be an external resource"))?
+                            .to_string(),
+                    );
+                }
+                "region_rect" => {
+                    let rect = arguments(value, "Rect2")?;
+                    let [x, y, width, height] = rect[..] else {
+                        return Err(invalid(format!("invalid region_rect {value}")));
+                    };
+                    // an empty region means the whole texture
+                    if width > 0. && height > 0. {
+                        style_box.region = Some(URect::new(
+                            x as u32,
+                            y as u32,
+                            (x + width) as u32,
+                            (y + height) as u32,
+                        ));
+                    }
+                }
+                "axis_stretch_horizontal" => style_box.horizontal_fill = axis_stretch(key, value)?,
+                "axis_stretch_vertical" => style_box.vertical_fill = axis_stretch(key, value)?,
+                "modulate_color" => {
+                    let color = arguments(value, "Color")?;
+                    let [r, g, b, a] = color[..] else {
+                        return Err(invalid(format!("invalid modulate_color {value}")));
+                    };
+                    style_box.modulate = Color::rgba(r, g, b, a);
+                }
+                _ => (),
+            },
+        }
+    }
+    let texture_id = texture_id.ok_or_else(|| invalid("the style box has no texture"))?;
+    style_box.texture = external_resources
+        .remove(&texture_id)
+        .ok_or_else(|| invalid(format!("external resource {texture_id} is not declared")))?;
+    Ok(style_box)
+}
+
+impl StyleBoxTexture {
+    fn expand_margin(&self) -> UiRect {
+        let [left, top, right, bottom] = self.expand_margin.map(Val::Px);
+        UiRect {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
+    fn builder(&self) -> NinePatchBuilder<String> {
+        let [left, top, right, bottom] = self.texture_margin.map(|margin| margin.round() as u32);
+        let mut builder = NinePatchBuilder::by_margins_with_content(
+            top,
+            bottom,
+            left,
+            right,
+            GODOT_CONTENT.to_string(),
+        )
+        .with_fill(self.horizontal_fill, self.vertical_fill)
+        .with_outset(self.expand_margin());
+        if let Some(region) = self.region {
+            builder = builder.with_region(region);
+        }
+        // content margins are from the edges of the style box, negative when not set
+        let [left, top, right, bottom] = [0, 1, 2, 3].map(|side| {
+            let content_margin = self.content_margin[side];
+            if content_margin < 0. {
+                Val::Px(0.)
+            } else {
+                Val::Px((content_margin - self.texture_margin[side]).max(0.))
+            }
+        });
+        if [left, top, right, bottom] != [Val::Px(0.); 4] {
+            builder = builder.with_content_layout(
+                GODOT_CONTENT.to_string(),
+                ContentLayout {
+                    padding: UiRect {
+                        left,
+                        right,
+                        top,
+                        bottom,
+                    },
+                    ..default()
+                },
+            );
+        }
+        builder
+    }
+}
+
+/// Asset loader for Godot `StyleBoxTexture` resources
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GodotStyleBoxLoader;
+
+impl AssetLoader for GodotStyleBoxLoader {
+    type Asset = GodotStyleBox;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut tres = String::new();
+            reader.read_to_string(&mut tres).await?;
+            let style_box = read_style_box(&tres)?;
+
+            let texture_path = match style_box.texture.strip_prefix("res://") {
+                Some(path) => PathBuf::from(path),
+                None => load_context
+                    .path()
+                    .parent()
+                    .map(|directory| directory.join(&style_box.texture))
+                    .unwrap_or_else(|| PathBuf::from(&style_box.texture)),
+            };
+            Ok(GodotStyleBox {
+                texture: load_context.load(texture_path),
+                nine_patch: load_context
+                    .add_labeled_asset(GODOT_NINE_PATCH.to_string(), style_box.builder()),
+                tint: style_box.modulate,
+                expand_margin: style_box.expand_margin(),
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tres"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{invalid, read_style_box, StyleBoxTexture};
+    use crate::FillMode;
+
+    #[test]
+    fn godot_3_style_box() {
+        let tres = r#"[gd_resource type="StyleBoxTexture" load_steps=2 format=2]
+
+[ext_resource path="res://button.png" type="Texture" id=1]
+
+[resource]
+texture = ExtResource( 1 )
+region_rect = Rect2( 0, 0, 0, 0 )
+margin_left = 6.0
+margin_right = 6.0
+margin_top = 5.0
+margin_bottom = 10.0
+expand_margin_bottom = 2.0
+axis_stretch_horizontal = 1
+"#;
+        assert_eq!(
+            read_style_box(tres),
+            Ok(StyleBoxTexture {
+                texture: "res://button.png".to_string(),
+                region: None,
+                texture_margin: [6., 5., 6., 10.],
+                expand_margin: [0., 0., 0., 2.],
+                content_margin: [-1.; 4],
+                horizontal_fill: FillMode::Repeat,
+                vertical_fill: FillMode::Stretch,
+                modulate: Color::WHITE,
+            })
+        );
+    }
+
+    #[test]
+    fn godot_4_style_box() {
+        let tres = r#"[gd_resource type="StyleBoxTexture" load_steps=2 format=3 uid="uid://b6x1ddyq0wk3r"]
+
+[ext_resource type="Texture2D" uid="uid://c8vcvy3tgndbp" path="ui/panel.png" id="1_x4kbn"]
+
+[resource]
+content_margin_left = 24.0
+texture = ExtResource("1_x4kbn")
+texture_margin_left = 20.0
+texture_margin_top = 20.0
+texture_margin_right = 20.0
+texture_margin_bottom = 20.0
+region_rect = Rect2(0, 0, 100, 50)
+modulate_color = Color(1, 0.5, 0, 0.8)
+"#;
+        assert_eq!(
+            read_style_box(tres),
+            Ok(StyleBoxTexture {
+                texture: "ui/panel.png".to_string(),
+                region: Some(URect::new(0, 0, 100, 50)),
+                texture_margin: [20.; 4],
+                expand_margin: [0.; 4],
+                content_margin: [24., -1., -1., -1.],
+                horizontal_fill: FillMode::Stretch,
+                vertical_fill: FillMode::Stretch,
+                modulate: Color::rgba(1., 0.5, 0., 0.8),
+            })
+        );
+    }
+
+    #[test]
+    fn missing_texture() {
+        let header = r#"[gd_resource type="StyleBoxTexture" format=3]
+
+[ext_resource type="Texture2D" path="res://button.png" id="1"]
+
+[resource]
+texture_margin_left = 6.0
+"#;
+        assert_eq!(
+            read_style_box(header),
+            Err(invalid("the style box has no texture"))
+        );
+        assert_eq!(
+            read_style_box(&format!("{header}texture = ExtResource(\"2\")\n")),
+            Err(invalid("external resource 2 is not declared"))
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::ops::Range;
+
+use bevy::prelude::*;
+
+use crate::{
+    ContentLayout, ContentSpan, NinePatchBuilder, NinePatchError, Patch, PatchSize, PatchTarget,
+};
+
+/// Name of the content zone of a `NinePatchBuilder` read from an Android `.9.png` image
+pub const ANDROID_CONTENT: &str = "content";
+
+/// Guides are opaque black pixels in the 1 pixel border of the image, other pixels of the border must be fully
+/// transparent, or opaque red for the layout bounds that are ignored
+fn is_guide(data: &[u8], width: u32, x: u32, y: u32) -> Result<bool, NinePatchError> {
+    let i = ((y * width + x) * 4) as usize;
+    match data[i..i + 4] {
+        [0, 0, 0, 255] => Ok(true),
+        [_, _, _, 0] | [255, 0, 0, 255] => Ok(false),
+        _ => Err(NinePatchError::InvalidGuides(format!(
+            "pixel {x},{y} of the border is not a guide, it must be opaque black or transparent"
+        ))),
+    }
+}
+
+/// Split a guide line into runs of guide and non guide pixels
+fn runs(marked: &[bool]) -> Vec<(Range<u32>, bool)> {
+    let mut runs: Vec<(Range<u32>, bool)> = vec![];
+    for (i, marked) in marked.iter().enumerate() {
+        match runs.last_mut() {
+            Some((range, run_marked)) if run_marked == marked => range.end = i as u32 + 1,
+            _ => runs.push((i as u32..i as u32 + 1, *marked)),
+        }
+    }
+    runs
+}
+
+/// The single range marked on a padding guide, or `None` if there is none
+fn padding(marked: &[bool], guide: &str) -> Result<Option<Range<u32>>, NinePatchError> {
+    let marked_runs: Vec<_> = runs(marked)
+        .into_iter()
+        .filter(|(_, marked)| *marked)
+        .collect();
+    match marked_runs.as_slice() {
+        [] => Ok(None),
+        [(range, _)] => Ok(Some(range.clone())),
+        _ => Err(NinePatchError::InvalidGuides(format!(
+            "the {guide} guide marks several content areas"
+        ))),
+    }
+}
+
+/// Target size of a patch: fixed for parts not marked as stretchable, otherwise growing in proportion of its size
+/// compared to all stretchable parts
+fn target(
+    range: &Range<u32>,
+    stretch: bool,
+    total_stretch: u32,
+    stretch_count: usize,
+) -> PatchTarget {
+    match (stretch, stretch_count) {
+        (false, _) => PatchTarget::Source,
+        (true, 1) => PatchTarget::Grow(1.),
+        (true, _) => PatchTarget::Grow(range.len() as f32 / total_stretch as f32),
+    }
+}
+
+impl NinePatchBuilder<String> {
+    /// Create a `NinePatchBuilder` from the guides in the 1 pixel border of an Android `.9.png` image, with `data` its
+    /// pixels in RGBA8. Stretchable parts marked on the top and left guides become growing patches, and the content
+    /// area marked on the bottom and right guides becomes the content zone [`ANDROID_CONTENT`], with padding if it
+    /// doesn't match patch boundaries.
+    ///
+    /// The `NinePatchBuilder` must be applied to the image without its 1 pixel border.
+    pub fn from_android_guides(size: UVec2, data: &[u8]) -> Result<Self, NinePatchError> {
+        if size.x < 3 || size.y < 3 {
+            return Err(NinePatchError::InvalidGuides(
+                "image is too small to have guides".to_string(),
+            ));
+        }
+        if data.len() != (size.x * size.y * 4) as usize {
+            return Err(NinePatchError::InvalidGuides(
+                "image data doesn't match its size".to_string(),
+            ));
+        }
+        let top: Vec<bool> = (1..size.x - 1)
+            .map(|x| is_guide(data, size.x, x, 0))
+            .collect::<Result<_, _>>()?;
+        let left: Vec<bool> = (1..size.y - 1)
+            .map(|y| is_guide(data, size.x, 0, y))
+            .collect::<Result<_, _>>()?;
+        let bottom: Vec<bool> = (1..size.x - 1)
+            .map(|x| is_guide(data, size.x, x, size.y - 1))
+            .collect::<Result<_, _>>()?;
+        let right: Vec<bool> = (1..size.y - 1)
+            .map(|y| is_guide(data, size.x, size.x - 1, y))
+            .collect::<Result<_, _>>()?;
+
+        let columns = runs(&top);
+        let rows = runs(&left);
+        let stretch_columns: Vec<_> = columns.iter().filter(|(_, s)| *s).collect();
+        let stretch_rows: Vec<_> = rows.iter().filter(|(_, s)| *s).collect();
+        if stretch_columns.is_empty() || stretch_rows.is_empty() {
+            return Err(NinePatchError::InvalidGuides(
+                "the top and left guides must mark stretchable parts".to_string(),
+            ));
+        }
+        let stretch_width: u32 = stretch_columns.iter().map(|(r, _)| r.len() as u32).sum();
+        let stretch_height: u32 = stretch_rows.iter().map(|(r, _)| r.len() as u32).sum();
+
+        // without padding guides, content uses the stretchable area
+        let content_x = padding(&bottom, "bottom")?.unwrap_or(
+            stretch_columns[0].0.start..stretch_columns[stretch_columns.len() - 1].0.end,
+        );
+        let content_y = padding(&right, "right")?
+            .unwrap_or(stretch_rows[0].0.start..stretch_rows[stretch_rows.len() - 1].0.end);
+
+        let patches = rows
+            .iter()
+            .map(|(row, row_stretch)| {
+                columns
+                    .iter()
+                    .map(|(column, column_stretch)| Patch {
+                        original_width: PatchSize::Fixed(column.len() as u32),
+                        original_height: PatchSize::Fixed(row.len() as u32),
+                        target_width: target(
+                            column,
+                            *column_stretch,
+                            stretch_width,
+                            stretch_columns.len(),
+                        ),
+                        target_height: target(
+                            row,
+                            *row_stretch,
+                            stretch_height,
+                            stretch_rows.len(),
+                        ),
+                        content: None,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // the content zone spans all patches overlapping the content area, padded to match it
+        let overlapping = |runs: &[(Range<u32>, bool)], area: &Range<u32>| {
+            let covered: Vec<usize> = runs
+                .iter()
+                .enumerate()
+                .filter(|(_, (range, _))| range.start < area.end && area.start < range.end)
+                .map(|(i, _)| i)
+                .collect();
+            let span = covered[0]..covered[covered.len() - 1] + 1;
+            let start_padding = area.start - runs[span.start].0.start;
+            let end_padding = runs[span.end - 1].0.end - area.end;
+            (span, start_padding as f32, end_padding as f32)
+        };
+        let (span_columns, left_padding, right_padding) = overlapping(&columns, &content_x);
+        let (span_rows, top_padding, bottom_padding) = overlapping(&rows, &content_y);
+
+        Ok(NinePatchBuilder::from_patches(patches)
+            .with_content_span(ContentSpan {
+                content: ANDROID_CONTENT.to_string(),
+                rows: span_rows,
+                columns: span_columns,
+            })
+            .with_content_layout(
+                ANDROID_CONTENT.to_string(),
+                ContentLayout {
+                    padding: UiRect {
+                        left: Val::Px(left_padding),
+                        right: Val::Px(right_padding),
+                        top: Val::Px(top_padding),
+                        bottom: Val::Px(bottom_padding),
+                    },
+                    ..default()
+                },
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        ContentSpan, NinePatchBuilder, NinePatchError, PatchSize, PatchTarget, ANDROID_CONTENT,
+    };
+
+    const GUIDE: [u8; 4] = [0, 0, 0, 255];
+
+    /// Image of `size` with white pixels inside, and guides at the given pixels of the top, left, bottom and right
+    /// borders, counted from the first pixel inside the border
+    fn image(size: UVec2, guides: [&[u32]; 4]) -> Vec<u8> {
+        let mut data = vec![0; (size.x * size.y * 4) as usize];
+        let mut set = |x: u32, y: u32, pixel: [u8; 4]| {
+            let i = ((y * size.x + x) * 4) as usize;
+            data[i..i + 4].copy_from_slice(&pixel);
+        };
+        for y in 1..size.y - 1 {
+            for x in 1..size.x - 1 {
+                set(x, y, [255; 4]);
+            }
+        }
+        let [top, left, bottom, right] = guides;
+        top.iter().for_each(|x| set(x + 1, 0, GUIDE));
+        left.iter().for_each(|y| set(0, y + 1, GUIDE));
+        bottom.iter().for_each(|x| set(x + 1, size.y - 1, GUIDE));
+        right.iter().for_each(|y| set(size.x - 1, y + 1, GUIDE));
+        data
+    }
+
+    fn sizes(builder: &NinePatchBuilder<String>) -> Vec<(PatchSize, PatchTarget)> {
+        builder.patches[0]
+            .iter()
+            .map(|patch| (patch.original_width, patch.target_width))
+            .collect()
+    }
+
+    fn padding(builder: &NinePatchBuilder<String>) -> [Val; 4] {
+        let padding = builder.content_layouts[ANDROID_CONTENT].padding;
+        [padding.left, padding.right, padding.top, padding.bottom]
+    }
+
+    #[test]
+    fn single_stretch_run() {
+        let size = UVec2::new(12, 8);
+        let builder = NinePatchBuilder::from_android_guides(
+            size,
+            &image(size, [&[3, 4, 5], &[2, 3], &[2, 3, 4, 5, 6], &[1, 2, 3, 4]]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            sizes(&builder),
+            vec![
+                (PatchSize::Fixed(3), PatchTarget::Source),
+                (PatchSize::Fixed(3), PatchTarget::Grow(1.)),
+                (PatchSize::Fixed(4), PatchTarget::Source),
+            ]
+        );
+        assert_eq!(builder.patches.len(), 3);
+        assert_eq!(builder.patches[1][0].original_height, PatchSize::Fixed(2));
+        assert_eq!(builder.patches[1][0].target_height, PatchTarget::Grow(1.));
+        // the content area covers all columns and rows, padded to the guides
+        assert_eq!(
+            builder.content_spans,
+            vec![ContentSpan {
+                content: ANDROID_CONTENT.to_string(),
+                rows: 0..3,
+                columns: 0..3,
+            }]
+        );
+        assert_eq!(
+            padding(&builder),
+            [Val::Px(2.), Val::Px(3.), Val::Px(1.), Val::Px(1.)]
+        );
+    }
+
+    #[test]
+    fn multiple_stretch_runs_grow_in_proportion() {
+        let size = UVec2::new(10, 5);
+        let builder = NinePatchBuilder::from_android_guides(
+            size,
+            &image(size, [&[1, 5, 6, 7], &[1], &[], &[]]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            sizes(&builder),
+            vec![
+                (PatchSize::Fixed(1), PatchTarget::Source),
+                (PatchSize::Fixed(1), PatchTarget::Grow(0.25)),
+                (PatchSize::Fixed(3), PatchTarget::Source),
+                (PatchSize::Fixed(3), PatchTarget::Grow(0.75)),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_padding_guides_use_the_stretch_area() {
+        let size = UVec2::new(8, 8);
+        let builder = NinePatchBuilder::from_android_guides(
+            size,
+            &image(size, [&[2, 3], &[1, 2, 3], &[], &[]]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.content_spans,
+            vec![ContentSpan {
+                content: ANDROID_CONTENT.to_string(),
+                rows: 1..2,
+                columns: 1..2,
+            }]
+        );
+        assert_eq!(padding(&builder), [Val::Px(0.); 4]);
+    }
+
+    #[test]
+    fn invalid_guides_are_errors() {
+        let size = UVec2::new(8, 8);
+        let guides = |guides| NinePatchBuilder::from_android_guides(size, &image(size, guides));
+        let invalid = |result: Result<_, NinePatchError>| {
+            matches!(result, Err(NinePatchError::InvalidGuides(_)))
+        };
+
+        // no stretchable part
+        assert!(invalid(guides([&[], &[1], &[], &[]])));
+        // several content areas
+        assert!(invalid(guides([&[1], &[1], &[0, 3], &[]])));
+        // pixel of the border that is neither a guide nor transparent
+        let mut data = image(size, [&[1], &[1], &[], &[]]);
+        data[4..8].copy_from_slice(&[128, 128, 128, 255]);
+        assert!(invalid(NinePatchBuilder::from_android_guides(size, &data)));
+        // layout bounds are ignored
+        data[4..8].copy_from_slice(&[255, 0, 0, 255]);
+        assert!(NinePatchBuilder::from_android_guides(size, &data).is_ok());
+        assert!(invalid(NinePatchBuilder::from_android_guides(
+            UVec2::new(2, 8),
+            &[0; 64]
+        )));
+        assert!(invalid(NinePatchBuilder::from_android_guides(
+            size,
+            &data[4..]
+        )));
+    }
+}
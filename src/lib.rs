@@ -10,8 +10,68 @@
 )]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "aseprite")]
+mod aseprite;
+#[cfg(feature = "aseprite")]
+pub use aseprite::{
+    AsepriteNinePatchLoader, AsepriteNinePatches, NinePatchAsepritePlugin, ASEPRITE_TEXTURE,
+};
+
+mod animation;
+pub use animation::{NinePatchAnimation, NinePatchFrame};
+
+mod content;
+pub use content::{ContentPolicy, NinePatchCommandsExt, NinePatchReady, NinePatchSlots};
+
+mod css;
+pub use css::{BorderImage, BorderImageValue};
+
+#[cfg(feature = "debug")]
+mod debug;
+#[cfg(feature = "debug")]
+pub use debug::{NinePatchDebug, NinePatchDebugPlugin};
+
+mod error;
+pub use error::NinePatchError;
+
+mod fill;
+pub use fill::FillMode;
+
+mod focus;
+pub use focus::NinePatchFocus;
+
+#[cfg(feature = "godot")]
+mod godot;
+#[cfg(feature = "godot")]
+pub use godot::{
+    GodotStyleBox, GodotStyleBoxLoader, NinePatchGodotPlugin, GODOT_CONTENT, GODOT_NINE_PATCH,
+};
+
+mod guides;
+pub use guides::ANDROID_CONTENT;
+
+#[cfg(feature = "libgdx")]
+mod libgdx;
+#[cfg(feature = "libgdx")]
+pub use libgdx::LibGdxNinePatch;
+
 mod ninepatch;
-pub use ninepatch::{NinePatch, NinePatchBuilder, NinePatchContent, Patch};
+pub use ninepatch::{
+    ContentLayout, ContentSpan, NinePatch, NinePatchBuilder, NinePatchCell, NinePatchContent,
+    NinePatchRow, Patch, PatchGrid, PatchSize, PatchTarget,
+};
 
 mod plugin;
 pub use plugin::*;
+
+#[cfg(test)]
+mod testing;
+
+mod tween;
+pub use tween::{
+    Easing, NinePatchBorderScaleTween, NinePatchSizeTween, NinePatchTintTween,
+    NinePatchTweenCompleted, NinePatchTweenProperty, Tween, Tweenable,
+};
+
+#[cfg(feature = "unity")]
+mod unity;
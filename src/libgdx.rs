@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{ContentLayout, ContentSpan, NinePatchBuilder, NinePatchError};
+
+/// A nine patch read from a LibGDX texture atlas
+#[derive(Debug)]
+pub struct LibGdxNinePatch {
+    /// Name of the image of the atlas page holding the nine patch
+    pub page: String,
+    /// The nine patch, with a content zone named after its region
+    pub nine_patch: NinePatchBuilder<String>,
+}
+
+fn invalid(reason: impl Into<String>) -> NinePatchError {
+    NinePatchError::InvalidLibGdxAtlas(reason.into())
+}
+
+fn numbers<const N: usize>(key: &str, value: &str) -> Result<[i64; N], NinePatchError> {
+    let numbers = value
+        .split(',')
+        .map(|number| number.trim().parse())
+        .collect::<Result<Vec<i64>, _>>()
+        .map_err(|_| invalid(format!("invalid {key}: {value}")))?;
+    numbers
+        .try_into()
+        .map_err(|_| invalid(format!("{key} should have {N} values, found {value}")))
+}
+
+#[derive(Debug, Default)]
+struct Region {
+    name: String,
+    page: String,
+    rotated: bool,
+    index: i64,
+    bounds: Option<[i64; 4]>,
+    /// Left, right, top, bottom
+    split: Option<[i64; 4]>,
+    /// Left, right, top, bottom
+    pad: Option<[i64; 4]>,
+}
+
+impl Region {
+    fn nine_patch(self) -> Result<Option<(String, LibGdxNinePatch)>, NinePatchError> {
+        // regions without split aren't nine patches
+        let Some(split) = self.split else {
+            return Ok(None);
+        };
+        let name = if self.index >= 0 {
+            format!("{}_{}", self.name, self.index)
+        } else {
+            self.name
+        };
+        if self.rotated {
+            return Err(invalid(format!("region {name} is rotated in the atlas")));
+        }
+        let [x, y, width, height] = self
+            .bounds
+            .ok_or_else(|| invalid(format!("region {name} has no bounds")))?;
+        if [x, y, width, height].iter().any(|value| *value < 0) || split.iter().any(|s| *s < 0) {
+            return Err(invalid(format!(
+                "region {name} has negative bounds or split"
+            )));
+        }
+        let [left, right, top, bottom] = split.map(|side| side as u32);
+        let mut nine_patch =
+            NinePatchBuilder::by_margins_with_content(top, bottom, left, right, name.clone())
+                .with_region(URect::new(
+                    x as u32,
+                    y as u32,
+                    (x + width) as u32,
+                    (y + height) as u32,
+                ));
+
+        // padding is from the edges of the region, and can cover the borders
+        if let Some(pad) = self.pad.filter(|pad| pad.iter().any(|side| *side >= 0)) {
+            // sides not padded use the split
+            let pad = [0, 1, 2, 3].map(|side| {
+                if pad[side] < 0 {
+                    split[side]
+                } else {
+                    pad[side]
+                }
+            });
+            let padding = |value: i64| Val::Px(value as f32);
+            if pad.iter().zip(split).all(|(pad, split)| *pad >= split) {
+                nine_patch = nine_patch.with_content_layout(
+                    name.clone(),
+                    ContentLayout {
+                        padding: UiRect {
+                            left: padding(pad[0] - split[0]),
+                            right: padding(pad[1] - split[1]),
+                            top: padding(pad[2] - split[2]),
+                            bottom: padding(pad[3] - split[3]),
+                        },
+                        ..default()
+                    },
+                );
+            } else {
+                // the content zone covers all patches, padded to the content area
+                nine_patch.patches[1][1].content = None;
+                nine_patch = nine_patch
+                    .with_content_span(ContentSpan {
+                        content: name.clone(),
+                        rows: 0..3,
+                        columns: 0..3,
+                    })
+                    .with_content_layout(
+                        name.clone(),
+                        ContentLayout {
+                            padding: UiRect {
+                                left: padding(pad[0]),
+                                right: padding(pad[1]),
+                                top: padding(pad[2]),
+                                bottom: padding(pad[3]),
+                            },
+                            ..default()
+                        },
+                    );
+            }
+        }
+        Ok(Some((
+            name,
+            LibGdxNinePatch {
+                page: self.page,
+                nine_patch,
+            },
+        )))
+    }
+}
+
+impl NinePatchBuilder<String> {
+    /// Create a `NinePatchBuilder` for each region with a `split` in a LibGDX texture atlas, by region name. Regions
+    /// that are frames of an animation are named `<name>_<index>`. The content zone of each is named like its
+    /// region, and padded according to its `pad`.
+    ///
+    /// Both the legacy format (`xy` and `size`) and the current format (`bounds`) are supported.
+    pub fn from_libgdx_atlas(
+        atlas: &str,
+    ) -> Result<HashMap<String, LibGdxNinePatch>, NinePatchError> {
+        let mut nine_patches = HashMap::new();
+        let mut page: Option<String> = None;
+        let mut in_page_header = false;
+        let mut region: Option<Region> = None;
+        for line in atlas.lines() {
+            let indented = line.starts_with(char::is_whitespace);
+            let line = line.trim();
+            if line.is_empty() {
+                // a blank line starts a new page
+                if let Some(region) = region.take() {
+                    nine_patches.extend(region.nine_patch()?);
+                }
+                page = None;
+                continue;
+            }
+            match line.split_once(':') {
+                Some((key, value)) => {
+                    let (key, value) = (key.trim(), value.trim());
+                    let Some(region) = region.as_mut().filter(|_| !in_page_header) else {
+                        // properties of the page
+                        continue;
+                    };
+                    match key {
+                        "rotate" => region.rotated = value != "false" && value != "0",
+                        "index" => region.index = numbers::<1>(key, value)?[0],
+                        "bounds" => region.bounds = Some(numbers(key, value)?),
+                        "xy" => {
+                            let [x, y] = numbers(key, value)?;
+                            let [_, _, width, height] = region.bounds.unwrap_or_default();
+                            region.bounds = Some([x, y, width, height]);
+                        }
+                        "size" => {
+                            let [width, height] = numbers(key, value)?;
+                            let [x, y, _, _] = region.bounds.unwrap_or_default();
+                            region.bounds = Some([x, y, width, height]);
+                        }
+                        "split" => region.split = Some(numbers(key, value)?),
+                        "pad" => region.pad = Some(numbers(key, value)?),
+                        _ => (),
+                    }
+                }
+                None if page.is_none() => {
+                    page = Some(line.to_string());
+                    in_page_header = true;
+                }
+                None if indented => {
+                    return Err(invalid(format!("unexpected line {line}")));
+                }
+                None => {
+                    if let Some(region) = region.take() {
+                        nine_patches.extend(region.nine_patch()?);
+                    }
+                    in_page_header = false;
+                    region = Some(Region {
+                        name: line.to_string(),
+                        page: page.clone().unwrap_or_default(),
+                        index: -1,
+                        ..default()
+                    });
+                }
+            }
+        }
+        if let Some(region) = region.take() {
+            nine_patches.extend(region.nine_patch()?);
+        }
+        Ok(nine_patches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{NinePatchBuilder, PatchSize};
+
+    /// Top, bottom, left and right margins of a builder
+    fn margins(builder: &NinePatchBuilder<String>) -> [PatchSize; 4] {
+        [
+            builder.patches[0][0].original_height,
+            builder.patches[2][0].original_height,
+            builder.patches[0][0].original_width,
+            builder.patches[0][2].original_width,
+        ]
+    }
+
+    #[test]
+    fn legacy_atlas() {
+        let atlas = "
+ui.png
+format: RGBA8888
+filter: Nearest,Nearest
+repeat: none
+button
+  rotate: false
+  xy: 2, 2
+  size: 48, 32
+  split: 6, 6, 5, 10
+  pad: -1, -1, -1, -1
+  orig: 48, 32
+  offset: 0, 0
+  index: -1
+icon
+  rotate: false
+  xy: 52, 2
+  size: 16, 16
+  orig: 16, 16
+  offset: 0, 0
+  index: -1
+";
+        let nine_patches = NinePatchBuilder::from_libgdx_atlas(atlas).unwrap();
+        // the icon has no split
+        assert_eq!(nine_patches.len(), 1);
+        let button = &nine_patches["button"];
+        assert_eq!(button.page, "ui.png");
+        assert_eq!(
+            margins(&button.nine_patch),
+            [5, 10, 6, 6].map(PatchSize::Fixed),
+            "splits are left, right, top, bottom"
+        );
+        assert_eq!(button.nine_patch.region, Some(URect::new(2, 2, 50, 34)));
+        // a pad of -1 is not padded
+        assert!(button.nine_patch.content_layouts.is_empty());
+    }
+
+    #[test]
+    fn atlas_with_bounds() {
+        let atlas = "ui.png
+size: 64, 64
+format: RGBA8888
+filter: Nearest, Nearest
+repeat: none
+panel
+  bounds: 0, 0, 32, 32
+  split: 8, 8, 8, 8
+  pad: 12, -1, 10, 8
+frame
+  index: 1
+  bounds: 32, 0, 16, 16
+  split: 4, 4, 4, 4
+";
+        let nine_patches = NinePatchBuilder::from_libgdx_atlas(atlas).unwrap();
+        let panel = &nine_patches["panel"].nine_patch;
+        assert_eq!(panel.region, Some(URect::new(0, 0, 32, 32)));
+        // the pad is from the edges of the region, sides with a pad of -1 use the split
+        assert_eq!(
+            panel.content_layouts["panel"].padding,
+            UiRect {
+                left: Val::Px(4.),
+                right: Val::Px(0.),
+                top: Val::Px(2.),
+                bottom: Val::Px(0.),
+            }
+        );
+        let frame = &nine_patches["frame_1"].nine_patch;
+        assert_eq!(frame.region, Some(URect::new(32, 0, 48, 16)));
+        assert_eq!(frame.patches[1][1].content.as_deref(), Some("frame_1"));
+    }
+}
@@ -0,0 +1,287 @@
+//! Inspect and convert nine patch definitions without starting a Bevy app.
+
+use std::{error::Error, path::Path};
+
+use bevy::math::{Rect, UVec2, Vec2};
+use bevy_ninepatch::{NinePatchBuilder, PatchGrid};
+use image::RgbaImage;
+
+const USAGE: &str = "Usage:
+    ninepatch inspect <image.png> --margins <top>,<bottom>,<left>,<right> [--preview <out.png> --size <width>x<height>]
+    ninepatch inspect <image.png> --definition <definition.ron> [--preview <out.png> --size <width>x<height>]
+    ninepatch convert <image.9.png> <definition.ron> <image.png>
+
+inspect: check the definition against the image, and print the patches and the minimum size.
+    Optionally render a preview of the nine patch at the given size.
+convert: read the guides of an Android .9.png image, and write its definition and the image without guides.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("inspect") => inspect(&args[1..]),
+        Some("convert") => convert(&args[1..]),
+        _ => Err(USAGE.into()),
+    };
+    if let Err(error) = result {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
+
+/// Value following `flag` in `args`
+fn option<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn parse_margins(margins: &str) -> Result<NinePatchBuilder<String>, Box<dyn Error>> {
+    let margins = margins
+        .split(',')
+        .map(|margin| margin.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()?;
+    match margins.as_slice() {
+        [top, bottom, left, right] => {
+            Ok(NinePatchBuilder::by_margins(*top, *bottom, *left, *right))
+        }
+        _ => Err("margins must be <top>,<bottom>,<left>,<right>".into()),
+    }
+}
+
+fn parse_size(size: &str) -> Result<Vec2, Box<dyn Error>> {
+    let (width, height) = size
+        .split_once('x')
+        .ok_or("size must be <width>x<height>")?;
+    Ok(Vec2::new(width.trim().parse()?, height.trim().parse()?))
+}
+
+fn inspect(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let image_path = args.first().ok_or(USAGE)?;
+    let image = image::open(image_path)?.to_rgba8();
+    let texture_size = UVec2::new(image.width(), image.height());
+
+    let builder = match (option(args, "--margins"), option(args, "--definition")) {
+        (Some(margins), None) => parse_margins(margins)?,
+        (None, Some(definition)) => ron::from_str(&std::fs::read_to_string(definition)?)?,
+        _ => return Err(USAGE.into()),
+    };
+    builder.validate(texture_size)?;
+
+    println!("texture: {}x{}", texture_size.x, texture_size.y);
+    for (line, (patches, rects)) in builder
+        .patches
+        .iter()
+        .zip(builder.resolve(texture_size))
+        .enumerate()
+    {
+        match builder.grid {
+            PatchGrid::Rows => println!("row {line}:"),
+            PatchGrid::Columns => println!("column {line}:"),
+        }
+        for (index, (patch, rect)) in patches.iter().zip(rects).enumerate() {
+            let (row, column) = match builder.grid {
+                PatchGrid::Rows => (line, index),
+                PatchGrid::Columns => (index, line),
+            };
+            print!(
+                "    patch {row},{column}: {}x{} at {},{}, width {:?}, height {:?}",
+                rect.width(),
+                rect.height(),
+                rect.min.x,
+                rect.min.y,
+                patch.target_width,
+                patch.target_height
+            );
+            match &patch.content {
+                Some(content) => println!(", content {content:?}"),
+                None => println!(),
+            }
+        }
+    }
+    for span in &builder.content_spans {
+        println!(
+            "content {:?} over rows {:?} and columns {:?}",
+            span.content, span.rows, span.columns
+        );
+    }
+    let min_size = builder.min_size(texture_size);
+    println!("minimum size: {}x{}", min_size.x, min_size.y);
+
+    if let Some(preview_path) = option(args, "--preview") {
+        let size = parse_size(option(args, "--size").ok_or("--preview needs a --size")?)?;
+        let size = size.max(min_size);
+        preview(&builder, &image, size).save(preview_path)?;
+        println!(
+            "preview at {}x{} written to {preview_path}",
+            size.x.ceil(),
+            size.y.ceil()
+        );
+    }
+    Ok(())
+}
+
+/// Render the nine patch at `size`, scaling each patch with nearest neighbor sampling
+fn preview(builder: &NinePatchBuilder<String>, image: &RgbaImage, size: Vec2) -> RgbaImage {
+    let texture_size = UVec2::new(image.width(), image.height());
+    let mut preview = RgbaImage::new(size.x.ceil() as u32, size.y.ceil() as u32);
+    let sources = builder.resolve(texture_size).into_iter().flatten();
+    let targets = builder.layout(texture_size, size).into_iter().flatten();
+    for (source, target) in sources.zip(targets) {
+        let target = Rect {
+            min: target.min.round(),
+            max: target.max.round(),
+        };
+        if source.width() == 0 || source.height() == 0 || target.is_empty() {
+            continue;
+        }
+        let scale = Vec2::new(
+            source.width() as f32 / target.width(),
+            source.height() as f32 / target.height(),
+        );
+        for y in target.min.y as u32..(target.max.y as u32).min(preview.height()) {
+            for x in target.min.x as u32..(target.max.x as u32).min(preview.width()) {
+                let offset =
+                    ((Vec2::new(x as f32, y as f32) - target.min + 0.5) * scale).as_uvec2();
+                let source_pixel = (source.min + offset).min(source.max - 1);
+                preview.put_pixel(x, y, *image.get_pixel(source_pixel.x, source_pixel.y));
+            }
+        }
+    }
+    preview
+}
+
+fn convert(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [image_path, definition_path, stripped_path] = args else {
+        return Err(USAGE.into());
+    };
+    let image = image::open(image_path)?.to_rgba8();
+    let size = UVec2::new(image.width(), image.height());
+    let builder = NinePatchBuilder::from_android_guides(size, image.as_raw())?;
+
+    let stripped = image::imageops::crop_imm(&image, 1, 1, size.x - 2, size.y - 2).to_image();
+    builder.validate(UVec2::new(stripped.width(), stripped.height()))?;
+    stripped.save(stripped_path)?;
+    std::fs::write(
+        definition_path,
+        ron::ser::to_string_pretty(&builder, ron::ser::PrettyConfig::default())?,
+    )?;
+    println!(
+        "definition written to {definition_path}, image without guides written to {}",
+        Path::new(stripped_path).display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use bevy::math::{UVec2, Vec2};
+    use image::{Rgba, RgbaImage};
+
+    use super::{convert, inspect, parse_margins, parse_size, preview};
+
+    /// Path of a file in the temporary directory, unique to this run
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ninepatch-{}-{name}", std::process::id()))
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// 3x3 image with a different color in each pixel
+    fn image() -> RgbaImage {
+        RgbaImage::from_fn(3, 3, |x, y| Rgba([x as u8 * 100, y as u8 * 100, 0, 255]))
+    }
+
+    #[test]
+    fn arguments_are_parsed() {
+        let builder = parse_margins("1, 2,3,4").unwrap();
+        assert_eq!(builder.min_size(UVec2::new(10, 10)), Vec2::new(7., 3.));
+        assert!(parse_margins("1,2,3").is_err());
+        assert!(parse_margins("1,2,3,a").is_err());
+        assert_eq!(parse_size("300x100").unwrap(), Vec2::new(300., 100.));
+        assert!(parse_size("300").is_err());
+    }
+
+    #[test]
+    fn preview_stretches_the_middle_patches() {
+        let image = image();
+        let builder = parse_margins("1,1,1,1").unwrap();
+
+        let preview = preview(&builder, &image, Vec2::new(5., 4.));
+
+        assert_eq!(preview.dimensions(), (5, 4));
+        for (x, y) in [(0, 0), (4, 0), (0, 3), (4, 3)] {
+            let source = (x.min(2), y.min(2));
+            assert_eq!(preview.get_pixel(x, y), image.get_pixel(source.0, source.1));
+        }
+        for x in 1..4 {
+            for y in 1..3 {
+                assert_eq!(preview.get_pixel(x, y), image.get_pixel(1, 1));
+            }
+        }
+    }
+
+    #[test]
+    fn inspect_validates_and_writes_the_preview() {
+        let image_path = temp_path("inspect.png");
+        let preview_path = temp_path("inspect-preview.png");
+        image().save(&image_path).unwrap();
+        let image_path = image_path.to_str().unwrap();
+
+        assert!(inspect(&args(&[image_path, "--margins", "2,2,1,1"])).is_err());
+        assert!(inspect(&args(&[image_path])).is_err());
+        inspect(&args(&[
+            image_path,
+            "--margins",
+            "1,1,1,1",
+            "--preview",
+            preview_path.to_str().unwrap(),
+            "--size",
+            "1x6",
+        ]))
+        .unwrap();
+
+        // the preview is never smaller than the minimum size
+        let preview = image::open(&preview_path).unwrap();
+        assert_eq!((preview.width(), preview.height()), (2, 6));
+        std::fs::remove_file(image_path).unwrap();
+        std::fs::remove_file(preview_path).unwrap();
+    }
+
+    #[test]
+    fn convert_writes_the_definition_and_the_image_without_guides() {
+        let mut guides = RgbaImage::new(5, 5);
+        for (x, y) in [(2, 0), (0, 2)] {
+            guides.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+        }
+        for (x, y) in (1..4).flat_map(|x| (1..4).map(move |y| (x, y))) {
+            guides.put_pixel(x, y, Rgba([255; 4]));
+        }
+        let (guides_path, definition_path, stripped_path) = (
+            temp_path("convert.9.png"),
+            temp_path("convert.ron"),
+            temp_path("convert.png"),
+        );
+        guides.save(&guides_path).unwrap();
+
+        convert(&args(&[
+            guides_path.to_str().unwrap(),
+            definition_path.to_str().unwrap(),
+            stripped_path.to_str().unwrap(),
+        ]))
+        .unwrap();
+
+        let stripped = image::open(&stripped_path).unwrap();
+        assert_eq!((stripped.width(), stripped.height()), (3, 3));
+        let definition: bevy_ninepatch::NinePatchBuilder<String> =
+            ron::from_str(&std::fs::read_to_string(&definition_path).unwrap()).unwrap();
+        assert_eq!(definition.min_size(UVec2::new(3, 3)), Vec2::new(2., 2.));
+        for path in [guides_path, definition_path, stripped_path] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -1,31 +1,242 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
 use bevy::{
     prelude::*,
     reflect::TypeUuid,
     render::render_resource::{Extent3d, TextureDimension, TextureFormat},
-    ui::FocusPolicy,
+    ui::{ContentSize, FocusPolicy},
 };
 
+use crate::{fill::TiledPatch, BorderImage, FillMode, NinePatchError, NinePatchShrink};
+
 /// Describe a patch in the original image, how it should grow and if it can have content
 #[derive(Debug, Clone, TypePath)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Patch<T: Clone + Send + Sync + 'static> {
-    /// Size of the patch in the original image
-    pub original_size: IVec2,
+    /// Width of the patch in the original image
+    pub original_width: PatchSize,
+    /// Height of the patch in the original image
+    pub original_height: PatchSize,
     /// Width of the patch rendered
-    pub target_width: Val,
+    pub target_width: PatchTarget,
     /// Height of the patch rendered
-    pub target_height: Val,
+    pub target_height: PatchTarget,
     /// Does this patch can contain content
     pub content: Option<T>,
 }
 
+impl<T: Clone + Send + Sync + 'static> Patch<T> {
+    /// The same patch with its horizontal and vertical axes swapped
+    fn transposed(&self) -> Self {
+        Patch {
+            original_width: self.original_height,
+            original_height: self.original_width,
+            target_width: self.target_height,
+            target_height: self.target_width,
+            content: self.content.clone(),
+        }
+    }
+}
+
+/// Size of a patch in the original image, along one axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PatchSize {
+    /// A number of pixels
+    Fixed(u32),
+    /// The size of the image minus a number of pixels, usually the margins around the patch
+    Remaining(u32),
+    /// A fraction of the size of the image, rounded to the nearest pixel
+    Fraction(f32),
+    /// The size of the image minus two fractions of it, each rounded to the nearest pixel like
+    /// `PatchSize::Fraction`, usually the margins around the patch
+    RemainingFractions(f32, f32),
+    /// No pixels of the image
+    Empty,
+}
+
+impl PatchSize {
+    /// Number of pixels of the patch, in an image of `total` pixels along this axis
+    pub fn resolve(self, total: u32) -> u32 {
+        match self {
+            PatchSize::Fixed(px) => px,
+            PatchSize::Remaining(px) => total.saturating_sub(px),
+            PatchSize::Fraction(fraction) => (total as f32 * fraction).round().max(0.) as u32,
+            PatchSize::RemainingFractions(start, end) => total.saturating_sub(
+                PatchSize::Fraction(start).resolve(total) + PatchSize::Fraction(end).resolve(total),
+            ),
+            PatchSize::Empty => 0,
+        }
+    }
+
+    /// Is the patch larger than an image of `total` pixels along this axis
+    fn exceeds(self, total: u32) -> bool {
+        match self {
+            PatchSize::Fixed(px) | PatchSize::Remaining(px) => px > total,
+            PatchSize::Fraction(fraction) => !(0. ..=1.).contains(&fraction),
+            PatchSize::RemainingFractions(start, end) => {
+                !((0. ..=1.).contains(&start) && (0. ..=1.).contains(&end) && start + end <= 1.)
+            }
+            PatchSize::Empty => false,
+        }
+    }
+}
+
+/// Sizes as they were given before `PatchSize`: a positive number of pixels, or the size of the image minus the
+/// opposite of a number that is `0` or negative
+impl From<i32> for PatchSize {
+    fn from(size: i32) -> Self {
+        if size > 0 {
+            PatchSize::Fixed(size as u32)
+        } else {
+            PatchSize::Remaining(size.unsigned_abs())
+        }
+    }
+}
+
+/// Size of a patch when rendered, along one axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PatchTarget {
+    /// The size of the patch in the original image
+    Source,
+    /// A number of pixels
+    Px(f32),
+    /// Grow to fill the free space, shared between growing patches in proportion to their weight
+    Grow(f32),
+    /// A percentage of the free space, left by patches that don't grow and the size in the original image of
+    /// patches that grow. Patches that grow share what remains
+    Percent(f32),
+}
+
+/// Targets as they were given before `PatchTarget`: `Val::Px(0.)` for the size in the original image,
+/// `Val::Px` for a number of pixels, `Val::Auto` to grow, and `Val::Percent` to grow with a weight of the percentage
+/// divided by 100
+impl From<Val> for PatchTarget {
+    fn from(target: Val) -> Self {
+        match target {
+            Val::Px(0.) => PatchTarget::Source,
+            Val::Px(px) => PatchTarget::Px(px),
+            Val::Percent(percent) => PatchTarget::Grow(percent / 100.),
+            _ => PatchTarget::Grow(1.),
+        }
+    }
+}
+
+/// How the patches of a `NinePatchBuilder` are arranged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PatchGrid {
+    /// `patches` are rows from top to bottom, each with its patches from left to right. All patches of a row have the
+    /// same height, and each row is split independently
+    #[default]
+    Rows,
+    /// `patches` are columns from left to right, each with its patches from top to bottom. All patches of a column
+    /// have the same width, and each column is split independently, for frames with different divisions on each
+    /// side like tabs or notches
+    Columns,
+}
+
+/// How content is placed inside a content zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ContentLayout {
+    /// Space between the edges of the content zone and its content
+    pub padding: UiRect,
+    /// Direction in which several contents are placed
+    pub flex_direction: FlexDirection,
+    /// How content is aligned along `flex_direction`
+    pub justify_content: JustifyContent,
+    /// How content is aligned perpendicular to `flex_direction`
+    pub align_items: AlignItems,
+}
+
+impl Default for ContentLayout {
+    fn default() -> Self {
+        ContentLayout {
+            padding: UiRect::DEFAULT,
+            flex_direction: FlexDirection::DEFAULT,
+            justify_content: JustifyContent::DEFAULT,
+            align_items: AlignItems::DEFAULT,
+        }
+    }
+}
+
+impl ContentLayout {
+    fn style(&self) -> Style {
+        Style {
+            flex_grow: 1.,
+            padding: self.padding,
+            flex_direction: self.flex_direction,
+            justify_content: self.justify_content,
+            align_items: self.align_items,
+            ..default()
+        }
+    }
+}
+
+/// A content zone covering a rectangle of patches, placed over them. It is placed from the layout of the patches, and
+/// follows a change of size of the 9-Patch UI element one frame after the patches
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContentSpan<T> {
+    /// Name of the content zone
+    pub content: T,
+    /// Rows of patches covered by the content zone, or patches covered in each of its columns for a grid of
+    /// `PatchGrid::Columns`
+    pub rows: std::ops::Range<usize>,
+    /// Patches covered by the content zone in each of its rows, or columns of patches covered for a grid of
+    /// `PatchGrid::Columns`
+    pub columns: std::ops::Range<usize>,
+}
+
 /// Holds the patches of a nine patch texture
 #[derive(Debug, TypePath, TypeUuid, Asset)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[uuid = "ec4c1e8f-d0fb-42f4-8bcd-49b71cea3a7d"]
 pub struct NinePatchBuilder<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static = ()>
 {
-    /// Patches for a nine patch texture. See example `full.rs` on how to use directly
+    /// Patches for a nine patch texture, as rows or columns depending on `grid`. See example `full.rs` on how to use
+    /// directly
     pub patches: Vec<Vec<Patch<T>>>,
+    /// Are `patches` rows or columns
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub grid: PatchGrid,
+    /// Layout of the content inside each content zone. Content zones not listed use the default `ContentLayout`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub content_layouts: HashMap<T, ContentLayout>,
+    /// Content zones covering several patches
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub content_spans: Vec<ContentSpan<T>>,
+    /// Part of the texture holding the patches, for textures with several elements like sprite sheets. The whole
+    /// texture is used if `None`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub region: Option<URect>,
+    /// How patches that grow horizontally fill their width
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub horizontal_fill: FillMode,
+    /// How patches that grow vertically fill their height
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub vertical_fill: FillMode,
+    /// Patches that are laid out but not drawn, by row and column
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hidden_patches: HashSet<(usize, usize)>,
+    /// CSS border image setting the size of the patches of a 3x3 grid from the size of the texture, see
+    /// [`NinePatchBuilder::from_border_image`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub border_image: Option<BorderImage>,
+    /// How far the patches are drawn outside of the node on each side, in pixels or percentages of the node, for
+    /// shadows and glows. The layout of the node and the area reacting to the pointer don't change. A border image
+    /// uses its own `outset` instead
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub outset: UiRect,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) patch_textures: Option<Vec<Handle<Image>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) original_texture: Option<Handle<Image>>,
 }
 
@@ -34,10 +245,60 @@ impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePat
     pub fn from_patches(patches: Vec<Vec<Patch<T>>>) -> Self {
         Self {
             patches,
+            grid: PatchGrid::Rows,
+            content_layouts: HashMap::new(),
+            content_spans: vec![],
+            region: None,
+            horizontal_fill: FillMode::Stretch,
+            vertical_fill: FillMode::Stretch,
+            hidden_patches: HashSet::new(),
+            border_image: None,
+            outset: UiRect::ZERO,
             patch_textures: None,
             original_texture: None,
         }
     }
+
+    /// Create a `NinePatchBuilder` from columns of patches, each with its patches from top to bottom, see
+    /// [`PatchGrid::Columns`]
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_ninepatch::*;
+    /// let patch = |original_width, target_width, original_height, target_height| Patch::<()> {
+    ///     original_width,
+    ///     original_height,
+    ///     target_width,
+    ///     target_height,
+    ///     content: None,
+    /// };
+    /// let (fixed, grow) = (PatchTarget::Source, PatchTarget::Grow(1.));
+    /// // the right column has a taller top border than the left one, like a notch
+    /// let builder = NinePatchBuilder::from_columns(vec![
+    ///     vec![
+    ///         patch(PatchSize::Fixed(10), fixed, PatchSize::Fixed(10), fixed),
+    ///         patch(PatchSize::Fixed(10), fixed, PatchSize::Remaining(20), grow),
+    ///         patch(PatchSize::Fixed(10), fixed, PatchSize::Fixed(10), fixed),
+    ///     ],
+    ///     vec![
+    ///         patch(PatchSize::Remaining(10), grow, PatchSize::Fixed(20), fixed),
+    ///         patch(PatchSize::Remaining(10), grow, PatchSize::Remaining(25), grow),
+    ///         patch(PatchSize::Remaining(10), grow, PatchSize::Fixed(5), fixed),
+    ///     ],
+    /// ]);
+    /// let texture_size = UVec2::new(30, 40);
+    /// assert_eq!(builder.validate(texture_size), Ok(()));
+    /// assert_eq!(builder.resolve(texture_size)[1][0], URect::new(10, 0, 30, 20));
+    /// let layout = builder.layout(texture_size, Vec2::new(100., 80.));
+    /// assert_eq!(layout[0][1], Rect::new(0., 10., 10., 70.));
+    /// assert_eq!(layout[1][1], Rect::new(10., 20., 100., 75.));
+    /// ```
+    pub fn from_columns(columns: Vec<Vec<Patch<T>>>) -> Self {
+        Self {
+            grid: PatchGrid::Columns,
+            ..Self::from_patches(columns)
+        }
+    }
 }
 
 impl<T: Clone + Send + Sync + Default + Eq + std::hash::Hash + TypePath + 'static>
@@ -58,9 +319,113 @@ impl<T: Clone + Send + Sync + Default + Eq + std::hash::Hash + TypePath + 'stati
             T::default(),
         )
     }
+
+    /// Create a simple nine patch with margins in fractions of the size of the texture, like `0.25` for a quarter
+    /// of it. Margins are resolved against the texture when the builder is applied, so the same builder can be used
+    /// with textures of different resolutions.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_ninepatch::NinePatchBuilder;
+    /// let builder = NinePatchBuilder::<()>::by_margin_fractions(0.25, 0.25, 0.125, 0.125);
+    /// assert_eq!(builder.resolve(UVec2::new(64, 64))[0][0], URect::new(0, 0, 8, 16));
+    /// assert_eq!(builder.resolve(UVec2::new(256, 256))[0][0], URect::new(0, 0, 32, 64));
+    /// ```
+    pub fn by_margin_fractions(
+        top_margin: f32,
+        bottom_margin: f32,
+        left_margin: f32,
+        right_margin: f32,
+    ) -> Self {
+        Self::by_margin_fractions_with_content(
+            top_margin,
+            bottom_margin,
+            left_margin,
+            right_margin,
+            T::default(),
+        )
+    }
+
+    /// Create a three patch row for elements that only stretch horizontally, like progress bars or sliders: fixed
+    /// patches for the left and right margins, and a growing patch between them. The patches take the full height of
+    /// the texture and are stretched to the height of the element. A margin of `0` has no patch.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_ninepatch::NinePatchBuilder;
+    /// let builder = NinePatchBuilder::<()>::horizontal(10, 0);
+    /// let layout = builder.layout(UVec2::new(30, 8), Vec2::new(200., 16.));
+    /// assert_eq!(layout, vec![vec![Rect::new(0., 0., 10., 16.), Rect::new(10., 0., 200., 16.)]]);
+    /// ```
+    pub fn horizontal(left_margin: u32, right_margin: u32) -> Self {
+        Self::horizontal_with_content(left_margin, right_margin, T::default())
+    }
+
+    /// Create a three patch column for elements that only stretch vertically: fixed patches for the top and bottom
+    /// margins, and a growing patch between them. The patches take the full width of the texture and are stretched
+    /// to the width of the element. A margin of `0` has no patch.
+    pub fn vertical(top_margin: u32, bottom_margin: u32) -> Self {
+        Self::vertical_with_content(top_margin, bottom_margin, T::default())
+    }
 }
 
 impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchBuilder<T> {
+    /// Create a three patch row, with the middle patch as the content zone `content`, see
+    /// [`NinePatchBuilder::horizontal`]
+    pub fn horizontal_with_content(left_margin: u32, right_margin: u32, content: T) -> Self {
+        let margin = |margin: u32| Patch {
+            original_width: PatchSize::Fixed(margin),
+            original_height: PatchSize::Remaining(0),
+            target_width: PatchTarget::Source,
+            target_height: PatchTarget::Grow(1.),
+            content: None,
+        };
+        let middle = Patch {
+            original_width: PatchSize::Remaining(left_margin + right_margin),
+            original_height: PatchSize::Remaining(0),
+            target_width: PatchTarget::Grow(1.),
+            target_height: PatchTarget::Grow(1.),
+            content: Some(content),
+        };
+        let row = [
+            (left_margin > 0).then(|| margin(left_margin)),
+            Some(middle),
+            (right_margin > 0).then(|| margin(right_margin)),
+        ];
+        Self::from_patches(vec![row.into_iter().flatten().collect()])
+    }
+
+    /// Create a three patch column, with the middle patch as the content zone `content`, see
+    /// [`NinePatchBuilder::vertical`]
+    pub fn vertical_with_content(top_margin: u32, bottom_margin: u32, content: T) -> Self {
+        let margin = |margin: u32| Patch {
+            original_width: PatchSize::Remaining(0),
+            original_height: PatchSize::Fixed(margin),
+            target_width: PatchTarget::Grow(1.),
+            target_height: PatchTarget::Source,
+            content: None,
+        };
+        let middle = Patch {
+            original_width: PatchSize::Remaining(0),
+            original_height: PatchSize::Remaining(top_margin + bottom_margin),
+            target_width: PatchTarget::Grow(1.),
+            target_height: PatchTarget::Grow(1.),
+            content: Some(content),
+        };
+        let column = [
+            (top_margin > 0).then(|| margin(top_margin)),
+            Some(middle),
+            (bottom_margin > 0).then(|| margin(bottom_margin)),
+        ];
+        Self::from_patches(
+            column
+                .into_iter()
+                .flatten()
+                .map(|patch| vec![patch])
+                .collect(),
+        )
+    }
+
     /// Create a simple nine patch split by creating fixed patch for the margins, and growing patches inside
     pub fn by_margins_with_content(
         top_margin: u32,
@@ -69,166 +434,798 @@ impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePat
         right_margin: u32,
         content: T,
     ) -> Self {
-        let top = vec![
-            Patch {
-                original_size: IVec2::new(left_margin as i32, top_margin as i32),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
-                content: None,
-            },
-            Patch {
-                original_size: IVec2::new(
-                    -(left_margin as i32) - right_margin as i32,
-                    top_margin as i32,
-                ),
-                target_width: Val::Auto,
-                target_height: Val::ZERO,
-                content: None,
-            },
-            Patch {
-                original_size: IVec2::new(right_margin as i32, top_margin as i32),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
-                content: None,
-            },
-        ];
-        let middle = vec![
-            Patch {
-                original_size: IVec2::new(
-                    left_margin as i32,
-                    -(top_margin as i32) - bottom_margin as i32,
-                ),
-                target_width: Val::ZERO,
-                target_height: Val::Auto,
-                content: None,
-            },
-            Patch {
-                original_size: IVec2::new(
-                    -(left_margin as i32) - right_margin as i32,
-                    -(top_margin as i32) - bottom_margin as i32,
-                ),
-                target_width: Val::Auto,
-                target_height: Val::Auto,
-                content: Some(content),
-            },
-            Patch {
-                original_size: IVec2::new(
-                    right_margin as i32,
-                    -(top_margin as i32) - bottom_margin as i32,
-                ),
-                target_width: Val::ZERO,
-                target_height: Val::Auto,
-                content: None,
-            },
+        // a margin of 0 is an empty patch, not the whole texture
+        let margin = |margin: u32| {
+            if margin == 0 {
+                PatchSize::Empty
+            } else {
+                PatchSize::Fixed(margin)
+            }
+        };
+        let columns = [
+            (margin(left_margin), PatchTarget::Source),
+            (
+                PatchSize::Remaining(left_margin + right_margin),
+                PatchTarget::Grow(1.),
+            ),
+            (margin(right_margin), PatchTarget::Source),
         ];
-        let bottom = vec![
-            Patch {
-                original_size: IVec2::new(left_margin as i32, bottom_margin as i32),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
-                content: None,
-            },
-            Patch {
-                original_size: IVec2::new(
-                    -(left_margin as i32) - right_margin as i32,
-                    bottom_margin as i32,
-                ),
-                target_width: Val::Auto,
-                target_height: Val::ZERO,
-                content: None,
-            },
-            Patch {
-                original_size: IVec2::new(right_margin as i32, bottom_margin as i32),
-                target_width: Val::ZERO,
-                target_height: Val::ZERO,
-                content: None,
-            },
+        let rows = [
+            (margin(top_margin), PatchTarget::Source),
+            (
+                PatchSize::Remaining(top_margin + bottom_margin),
+                PatchTarget::Grow(1.),
+            ),
+            (margin(bottom_margin), PatchTarget::Source),
         ];
+        let [top, middle, bottom] = rows.map(|(original_height, target_height)| {
+            columns
+                .map(|(original_width, target_width)| Patch {
+                    original_width,
+                    original_height,
+                    target_width,
+                    target_height,
+                    content: None,
+                })
+                .to_vec()
+        });
+        let mut middle = middle;
+        middle[1].content = Some(content);
         Self {
             patches: vec![top, middle, bottom],
+            grid: PatchGrid::Rows,
+            content_layouts: HashMap::new(),
+            content_spans: vec![],
+            region: None,
+            horizontal_fill: FillMode::Stretch,
+            vertical_fill: FillMode::Stretch,
+            hidden_patches: HashSet::new(),
+            border_image: None,
+            outset: UiRect::ZERO,
             patch_textures: None,
             original_texture: None,
         }
     }
 }
 
-fn to_width(patch: IVec2, total: Extent3d) -> u32 {
-    if patch.x > 0 {
-        patch.x as u32
-    } else {
-        (total.width as i32 + patch.x) as u32
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchBuilder<T> {
+    /// Create a simple nine patch with margins in fractions of the size of the texture, see
+    /// [`NinePatchBuilder::by_margin_fractions`]
+    pub fn by_margin_fractions_with_content(
+        top_margin: f32,
+        bottom_margin: f32,
+        left_margin: f32,
+        right_margin: f32,
+        content: T,
+    ) -> Self {
+        let mut builder = Self::by_margins_with_content(0, 0, 0, 0, content);
+        let columns = [
+            PatchSize::Fraction(left_margin),
+            PatchSize::RemainingFractions(left_margin, right_margin),
+            PatchSize::Fraction(right_margin),
+        ];
+        let rows = [
+            PatchSize::Fraction(top_margin),
+            PatchSize::RemainingFractions(top_margin, bottom_margin),
+            PatchSize::Fraction(bottom_margin),
+        ];
+        for (row, original_height) in builder.patches.iter_mut().zip(rows) {
+            for (patch, original_width) in row.iter_mut().zip(columns) {
+                patch.original_width = original_width;
+                patch.original_height = original_height;
+            }
+        }
+        builder
+    }
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchBuilder<T> {
+    /// Set the padding and alignment of the content inside the content zone `content`
+    pub fn with_content_layout(mut self, content: T, layout: ContentLayout) -> Self {
+        self.content_layouts.insert(content, layout);
+        self
+    }
+
+    /// Add a content zone covering several patches. Patches in this span don't need to be marked as content
+    pub fn with_content_span(mut self, span: ContentSpan<T>) -> Self {
+        self.content_spans.push(span);
+        self
+    }
+
+    /// Only use the part `region` of the texture, in pixels
+    pub fn with_region(mut self, region: URect) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Set how patches that grow fill the space on each axis, instead of stretching
+    pub fn with_fill(mut self, horizontal: FillMode, vertical: FillMode) -> Self {
+        self.horizontal_fill = horizontal;
+        self.vertical_fill = vertical;
+        self
+    }
+
+    /// Don't draw the patch at `column` in `row`, it still takes space and can have content
+    pub fn with_hidden_patch(mut self, row: usize, column: usize) -> Self {
+        self.hidden_patches.insert((row, column));
+        self
+    }
+
+    /// Set how far the patches are drawn outside of the node on each side
+    pub fn with_outset(mut self, outset: UiRect) -> Self {
+        self.outset = outset;
+        self
+    }
+
+    /// Part of a texture of size `texture_size` holding the patches
+    fn area(&self, texture_size: UVec2) -> URect {
+        self.region
+            .unwrap_or(URect::from_corners(UVec2::ZERO, texture_size))
+    }
+
+    /// Outset of the patches, from the border image if there is one
+    fn border_outset(&self) -> UiRect {
+        let Some(border_image) = self.border_image.filter(|_| is_3x3(&self.patches)) else {
+            return self.outset;
+        };
+        let [top, right, bottom, left] = border_image.outsets().map(Val::Px);
+        UiRect {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
+    /// Patches with their size for an area of size `area_size` in the texture, which depends on it for a border image
+    fn sized_patches(&self, area_size: UVec2) -> Cow<'_, [Vec<Patch<T>>]> {
+        let Some(border_image) = self.border_image.filter(|_| is_3x3(&self.patches)) else {
+            return Cow::Borrowed(&self.patches);
+        };
+        let size = area_size;
+        let slices = border_image.slices(size);
+        let [top, right, bottom, left] = slices;
+        let [top_width, right_width, bottom_width, left_width] = border_image.widths(slices);
+        let source = |px: u32| {
+            if px == 0 {
+                PatchSize::Empty
+            } else {
+                PatchSize::Fixed(px)
+            }
+        };
+        let columns = [
+            (source(left), Some(left_width)),
+            (source(size.x.saturating_sub(left + right)), None),
+            (source(right), Some(right_width)),
+        ];
+        let rows = [
+            (source(top), Some(top_width)),
+            (source(size.y.saturating_sub(top + bottom)), None),
+            (source(bottom), Some(bottom_width)),
+        ];
+        let mut patches = self.patches.clone();
+        for (row, (height, target_height)) in patches.iter_mut().zip(rows) {
+            for (patch, (width, target_width)) in row.iter_mut().zip(columns) {
+                patch.original_width = width;
+                patch.original_height = height;
+                if let Some(target_width) = target_width {
+                    patch.target_width = PatchTarget::Px(target_width);
+                }
+                if let Some(target_height) = target_height {
+                    patch.target_height = PatchTarget::Px(target_height);
+                }
+            }
+        }
+        Cow::Owned(patches)
+    }
+}
+
+fn to_width<T: Clone + Send + Sync + 'static>(patch: &Patch<T>, total: Extent3d) -> u32 {
+    patch.original_width.resolve(total.width)
+}
+fn to_height<T: Clone + Send + Sync + 'static>(patch: &Patch<T>, total: Extent3d) -> u32 {
+    patch.original_height.resolve(total.height)
+}
+
+fn row_height<T: Clone + Send + Sync + 'static>(row: &[Patch<T>], texture_size: Extent3d) -> Val {
+    row.first().map_or(Val::ZERO, |p| match p.target_height {
+        PatchTarget::Source => Val::Px(to_height(p, texture_size) as f32),
+        PatchTarget::Px(height) => Val::Px(height),
+        PatchTarget::Grow(_) | PatchTarget::Percent(_) => Val::Auto,
+    })
+}
+
+fn patch_width<T: Clone + Send + Sync + 'static>(patch: &Patch<T>, texture_size: Extent3d) -> Val {
+    match patch.target_width {
+        PatchTarget::Source => Val::Px(to_width(patch, texture_size) as f32),
+        PatchTarget::Px(width) => Val::Px(width),
+        PatchTarget::Grow(_) | PatchTarget::Percent(_) => Val::Auto,
+    }
+}
+
+fn patch_height<T: Clone + Send + Sync + 'static>(patch: &Patch<T>, texture_size: Extent3d) -> Val {
+    match patch.target_height {
+        PatchTarget::Source => Val::Px(to_height(patch, texture_size) as f32),
+        PatchTarget::Px(height) => Val::Px(height),
+        PatchTarget::Grow(_) | PatchTarget::Percent(_) => Val::Auto,
+    }
+}
+
+/// Flex grow factor of each target placed one after the other: a percentage takes that part of the free space, and
+/// patches that grow share what remains in proportion to their weight
+fn growths(targets: impl Iterator<Item = PatchTarget> + Clone) -> Vec<f32> {
+    let percent = |target| match target {
+        PatchTarget::Percent(percent) => percent.max(0.) / 100.,
+        _ => 0.,
+    };
+    let weight = |target| match target {
+        PatchTarget::Grow(weight) => weight.max(0.),
+        _ => 0.,
+    };
+    let remaining = 1. - targets.clone().map(percent).sum::<f32>().min(1.);
+    let weights = targets.clone().map(weight).sum::<f32>();
+    targets
+        .map(|target| match target {
+            PatchTarget::Percent(_) => percent(target),
+            PatchTarget::Grow(_) if weights > 0. => weight(target) / weights * remaining,
+            _ => 0.,
+        })
+        .collect()
+}
+
+/// Size before growing of a patch of `source` pixels in the original image: percentages start from nothing
+fn basis(size: Val, target: PatchTarget, source: u32) -> f32 {
+    match (size, target) {
+        (Val::Px(size), _) => size,
+        (_, PatchTarget::Percent(_)) => 0.,
+        _ => source as f32,
+    }
+}
+
+/// Flex basis and minimum size of a patch or row of `size`, matching its [`basis`]. The size of the image is not
+/// measured, so patches that grow start from their `source` size, and percentages can shrink to nothing
+fn flex_start(size: Val, target: PatchTarget, source: u32) -> (Val, Val) {
+    match (size, target) {
+        (Val::Px(_), _) => (Val::Auto, Val::Auto),
+        (_, PatchTarget::Percent(_)) => (Val::Px(0.), Val::Px(0.)),
+        _ => (Val::Px(basis(size, target, source)), Val::Auto),
     }
 }
-fn to_height(patch: IVec2, total: Extent3d) -> u32 {
-    if patch.y > 0 {
-        patch.y as u32
+
+fn min_size<T: Clone + Send + Sync + 'static>(
+    patches: &[Vec<Patch<T>>],
+    texture_size: Extent3d,
+) -> Vec2 {
+    let width = patches
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|patch| match patch_width(patch, texture_size) {
+                    Val::Px(width) => width,
+                    _ => 0.,
+                })
+                .sum::<f32>()
+        })
+        .fold(0., f32::max);
+    let height = patches
+        .iter()
+        .map(|row| match row_height(row, texture_size) {
+            Val::Px(height) => height,
+            _ => 0.,
+        })
+        .sum::<f32>();
+    Vec2::new(width, height)
+}
+
+/// Sizes of flex items placed one after the other in `available` space, from their base size and how they grow and
+/// shrink. This mirrors how the UI layout distributes space between patches.
+fn distribute(items: &[(f32, f32)], available: f32) -> Vec<f32> {
+    let free = available - items.iter().map(|(basis, _)| basis).sum::<f32>();
+    let factors: Vec<f32> = if free >= 0. {
+        items.iter().map(|(_, flex)| *flex).collect()
     } else {
-        (total.height as i32 + patch.y) as u32
+        // shrinking is weighted by the base size
+        items.iter().map(|(basis, flex)| basis * flex).collect()
+    };
+    let total = factors.iter().sum::<f32>();
+    if total <= 0. {
+        return items.iter().map(|(basis, _)| *basis).collect();
+    }
+    // when the flex factors sum to less than one, only that part of the free space is distributed
+    let distributed = if free >= 0. {
+        free * total.min(1.)
+    } else {
+        free
+    };
+    items
+        .iter()
+        .zip(factors)
+        .map(|((basis, _), factor)| (basis + distributed * factor / total).max(0.))
+        .collect()
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchBuilder<T> {
+    /// Rectangle of each patch in a texture of size `texture_size`
+    pub fn resolve(&self, texture_size: UVec2) -> Vec<Vec<URect>> {
+        self.resolve_in(self.area(texture_size))
+    }
+
+    /// Rectangle of each patch, with the patches taken from `area` of the texture
+    fn resolve_in(&self, area: URect) -> Vec<Vec<URect>> {
+        if self.grid == PatchGrid::Columns {
+            let transpose_rect =
+                |rect: URect| URect::from_corners(transpose(rect.min), transpose(rect.max));
+            return transpose_lines(
+                self.transposed().resolve_in(transpose_rect(area)),
+                transpose_rect,
+            );
+        }
+        let patches = self.sized_patches(area.size());
+        let texture_size = extent(area.size());
+        let mut accu_y = area.min.y;
+        patches
+            .iter()
+            .map(|row| {
+                let mut accu_x = area.min.x;
+                let rects = row
+                    .iter()
+                    .map(|column_item| {
+                        let width = to_width(column_item, texture_size);
+                        let height = to_height(column_item, texture_size);
+                        let rect = URect::new(accu_x, accu_y, accu_x + width, accu_y + height);
+                        accu_x += width;
+                        rect
+                    })
+                    .collect();
+                accu_y += row
+                    .first()
+                    .map_or(0, |patch| to_height(patch, texture_size));
+                rects
+            })
+            .collect()
+    }
+
+    /// Check that the patches can be applied to a texture of size `texture_size`: the region is inside the texture,
+    /// all patches are inside the region, all patches in a row have the same height in the texture and when
+    /// rendered, and content spans cover existing patches. Rows don't need to have the same number of patches, or
+    /// patches of the same width.
+    pub fn validate(&self, texture_size: UVec2) -> Result<(), NinePatchError> {
+        if self.grid == PatchGrid::Columns {
+            // errors are found on rows of the transposed grid, which are columns
+            return self.transposed().validate(transpose(texture_size)).map_err(
+                |error| match error {
+                    NinePatchError::RegionOutOfTexture(region) => {
+                        NinePatchError::RegionOutOfTexture(URect::from_corners(
+                            transpose(region.min),
+                            transpose(region.max),
+                        ))
+                    }
+                    NinePatchError::PatchOutOfTexture { row, column } => {
+                        NinePatchError::PatchOutOfTexture {
+                            row: column,
+                            column: row,
+                        }
+                    }
+                    NinePatchError::UnevenRow {
+                        row,
+                        column,
+                        expected,
+                        found,
+                    } => NinePatchError::UnevenColumn {
+                        column: row,
+                        row: column,
+                        expected,
+                        found,
+                    },
+                    NinePatchError::MisalignedRow { row, column } => {
+                        NinePatchError::MisalignedColumn {
+                            column: row,
+                            row: column,
+                        }
+                    }
+                    NinePatchError::SpanOutOfGrid { rows, columns } => {
+                        NinePatchError::SpanOutOfGrid {
+                            rows: columns,
+                            columns: rows,
+                        }
+                    }
+                    other => other,
+                },
+            );
+        }
+        if self.patches.is_empty() || self.patches.iter().any(|row| row.is_empty()) {
+            return Err(NinePatchError::Empty);
+        }
+        let area = self.area(texture_size);
+        if area.max.x > texture_size.x || area.max.y > texture_size.y {
+            return Err(NinePatchError::RegionOutOfTexture(area));
+        }
+        if self.border_image.is_some() && !is_3x3(&self.patches) {
+            return Err(NinePatchError::InvalidBorderImage(
+                "a border image needs 3 rows of 3 patches".to_string(),
+            ));
+        }
+        let patches = self.sized_patches(area.size());
+        for (row_index, row) in patches.iter().enumerate() {
+            for (column, patch) in row.iter().enumerate() {
+                if patch.original_width.exceeds(area.width())
+                    || patch.original_height.exceeds(area.height())
+                {
+                    return Err(NinePatchError::PatchOutOfTexture {
+                        row: row_index,
+                        column,
+                    });
+                }
+            }
+        }
+        let rects = self.resolve(texture_size);
+        for (row_index, row) in rects.iter().enumerate() {
+            let expected = row[0].height();
+            for (column, rect) in row.iter().enumerate() {
+                if rect.height() != expected {
+                    return Err(NinePatchError::UnevenRow {
+                        row: row_index,
+                        column,
+                        expected,
+                        found: rect.height(),
+                    });
+                }
+                if rect.max.x > area.max.x || rect.max.y > area.max.y {
+                    return Err(NinePatchError::PatchOutOfTexture {
+                        row: row_index,
+                        column,
+                    });
+                }
+            }
+        }
+        // rows are split independently, but all patches of a row are rendered at the same height
+        let extent = extent(area.size());
+        for (row_index, row) in patches.iter().enumerate() {
+            let expected = patch_height(&row[0], extent);
+            for (column, patch) in row.iter().enumerate() {
+                if patch_height(patch, extent) != expected {
+                    return Err(NinePatchError::MisalignedRow {
+                        row: row_index,
+                        column,
+                    });
+                }
+            }
+        }
+        for span in &self.content_spans {
+            let in_grid = !span.rows.is_empty()
+                && !span.columns.is_empty()
+                && span.rows.clone().all(|row| {
+                    self.patches
+                        .get(row)
+                        .is_some_and(|row| span.columns.end <= row.len())
+                });
+            if !in_grid {
+                return Err(NinePatchError::SpanOutOfGrid {
+                    rows: span.rows.clone(),
+                    columns: span.columns.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Smallest size a `NinePatch` from these patches can take without squashing its fixed patches, for a texture of
+    /// size `texture_size`
+    pub fn min_size(&self, texture_size: UVec2) -> Vec2 {
+        if self.grid == PatchGrid::Columns {
+            return self.transposed().min_size(transpose(texture_size)).yx();
+        }
+        min_size(
+            &self.sized_patches(self.area(texture_size).size()),
+            extent(self.area(texture_size).size()),
+        )
+    }
+
+    /// Rectangle of each patch when displayed at `size`, for a texture of size `texture_size`. Patches that grow
+    /// start from their size in the texture, and share the remaining space.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_ninepatch::*;
+    /// let patch = |target_width| Patch::<()> {
+    ///     original_width: PatchSize::Fixed(10),
+    ///     original_height: PatchSize::Remaining(0),
+    ///     target_width,
+    ///     target_height: PatchTarget::Grow(1.),
+    ///     content: None,
+    /// };
+    /// let builder = NinePatchBuilder::from_patches(vec![vec![
+    ///     patch(PatchTarget::Source),
+    ///     patch(PatchTarget::Percent(50.)),
+    ///     patch(PatchTarget::Grow(1.)),
+    ///     patch(PatchTarget::Grow(3.)),
+    /// ]]);
+    /// let layout = builder.layout(UVec2::new(40, 10), Vec2::new(250., 10.));
+    /// let widths: Vec<f32> = layout[0].iter().map(|rect| rect.width()).collect();
+    /// // 220 pixels are free: half of them for the percentage, the other half shared 1 to 3
+    /// assert_eq!(widths, vec![10., 110., 10. + 27.5, 10. + 82.5]);
+    /// ```
+    pub fn layout(&self, texture_size: UVec2, size: Vec2) -> Vec<Vec<Rect>> {
+        if self.grid == PatchGrid::Columns {
+            return transpose_lines(
+                self.transposed().layout(transpose(texture_size), size.yx()),
+                |rect| Rect::from_corners(rect.min.yx(), rect.max.yx()),
+            );
+        }
+        let area_size = self.area(texture_size).size();
+        let patches = self.sized_patches(area_size);
+        let texture_size = extent(area_size);
+        let row_growths = growths(patches.iter().map(|row| row_target(row)));
+        let rows: Vec<(f32, f32)> = patches
+            .iter()
+            .zip(row_growths)
+            .map(|(row, growth)| {
+                let source = row
+                    .first()
+                    .map_or(0, |patch| to_height(patch, texture_size));
+                (
+                    basis(row_height(row, texture_size), row_target(row), source),
+                    growth,
+                )
+            })
+            .collect();
+        let heights = distribute(&rows, size.y);
+
+        let mut y = 0.;
+        patches
+            .iter()
+            .zip(heights)
+            .map(|(row, row_height)| {
+                let columns: Vec<(f32, f32)> = row
+                    .iter()
+                    .zip(growths(row.iter().map(|patch| patch.target_width)))
+                    .map(|(patch, growth)| {
+                        let source = to_width(patch, texture_size);
+                        (
+                            basis(patch_width(patch, texture_size), patch.target_width, source),
+                            growth,
+                        )
+                    })
+                    .collect();
+                let mut x = 0.;
+                let rects = row
+                    .iter()
+                    .zip(distribute(&columns, size.x))
+                    .map(|(patch, width)| {
+                        let height = match patch_height(patch, texture_size) {
+                            Val::Px(height) => height,
+                            _ => row_height,
+                        };
+                        let rect = Rect::new(x, y, x + width, y + height);
+                        x += width;
+                        rect
+                    })
+                    .collect();
+                y += row_height;
+                rects
+            })
+            .collect()
+    }
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchBuilder<T> {
+    /// The patches of a grid of columns as a grid of rows, with their horizontal and vertical axes swapped. Sizes and
+    /// positions computed with it must be transposed back
+    fn transposed(&self) -> Self {
+        Self {
+            patches: self
+                .patches
+                .iter()
+                .map(|line| line.iter().map(Patch::transposed).collect())
+                .collect(),
+            grid: PatchGrid::Rows,
+            content_layouts: self.content_layouts.clone(),
+            content_spans: self
+                .content_spans
+                .iter()
+                .map(|span| ContentSpan {
+                    content: span.content.clone(),
+                    rows: span.columns.clone(),
+                    columns: span.rows.clone(),
+                })
+                .collect(),
+            region: self
+                .region
+                .map(|region| URect::from_corners(transpose(region.min), transpose(region.max))),
+            horizontal_fill: self.vertical_fill,
+            vertical_fill: self.horizontal_fill,
+            hidden_patches: self
+                .hidden_patches
+                .iter()
+                .map(|(row, column)| (*column, *row))
+                .collect(),
+            border_image: self.border_image.map(BorderImage::transposed),
+            outset: UiRect {
+                left: self.outset.top,
+                right: self.outset.bottom,
+                top: self.outset.left,
+                bottom: self.outset.right,
+            },
+            patch_textures: None,
+            original_texture: None,
+        }
+    }
+}
+
+fn transpose(size: UVec2) -> UVec2 {
+    UVec2::new(size.y, size.x)
+}
+
+/// Transpose each item of the lines of a transposed grid
+fn transpose_lines<R>(lines: Vec<Vec<R>>, transpose: impl Fn(R) -> R) -> Vec<Vec<R>> {
+    lines
+        .into_iter()
+        .map(|line| line.into_iter().map(&transpose).collect())
+        .collect()
+}
+
+/// How a row grows, from its first patch
+fn row_target<T: Clone + Send + Sync + 'static>(row: &[Patch<T>]) -> PatchTarget {
+    row.first()
+        .map_or(PatchTarget::Source, |patch| patch.target_height)
+}
+
+fn is_3x3<T: Clone + Send + Sync + 'static>(patches: &[Vec<Patch<T>>]) -> bool {
+    patches.len() == 3 && patches.iter().all(|row| row.len() == 3)
+}
+
+/// Add the components of a `NodeBundle` that `root` doesn't have yet
+fn insert_missing_node_components(root: &mut EntityWorldMut) {
+    fn insert_missing<C: Component>(root: &mut EntityWorldMut, component: C) {
+        if !root.contains::<C>() {
+            root.insert(component);
+        }
+    }
+    let NodeBundle {
+        node,
+        style,
+        background_color,
+        border_color,
+        focus_policy,
+        transform,
+        global_transform,
+        visibility,
+        inherited_visibility,
+        view_visibility,
+        z_index,
+    } = NodeBundle::default();
+    insert_missing(root, node);
+    insert_missing(root, style);
+    insert_missing(root, background_color);
+    insert_missing(root, border_color);
+    insert_missing(root, focus_policy);
+    insert_missing(root, transform);
+    insert_missing(root, global_transform);
+    insert_missing(root, visibility);
+    insert_missing(root, inherited_visibility);
+    insert_missing(root, view_visibility);
+    insert_missing(root, z_index);
+}
+
+/// Inset of a node drawn `outset` outside of its parent
+fn outside(outset: Val) -> Val {
+    match outset {
+        Val::Px(px) => Val::Px(-px),
+        Val::Percent(percent) => Val::Percent(-percent),
+        _ => Val::ZERO,
+    }
+}
+
+fn extent(size: UVec2) -> Extent3d {
+    Extent3d {
+        width: size.x,
+        height: size.y,
+        depth_or_array_layers: 1,
+    }
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchBuilder<T> {
+    /// Split a texture in one texture per patch, taking the patches from `area` of the texture instead of the region
+    /// of the builder if set. `None` if the texture isn't loaded, or if patches are outside of it
+    pub(crate) fn split(
+        &self,
+        texture_handle: &Handle<Image>,
+        area: Option<URect>,
+        textures: &mut Assets<Image>,
+    ) -> Option<Vec<Handle<Image>>> {
+        let texture = textures.get(texture_handle)?;
+        let (texture_size, texture_data) = (texture.texture_descriptor.size, &texture.data);
+        let area =
+            area.unwrap_or_else(|| self.area(UVec2::new(texture_size.width, texture_size.height)));
+        let rects = self.resolve_in(area);
+        // patches past the right edge would read the next lines of the texture, and past the bottom edge panic
+        if rects
+            .iter()
+            .flatten()
+            .any(|rect| rect.max.x > texture_size.width || rect.max.y > texture_size.height)
+        {
+            return None;
+        }
+        let mut textures_to_add = vec![];
+        for rect in rects.iter().flatten() {
+            let mut patch_texture_data = vec![];
+            for j in rect.min.y as usize..rect.max.y as usize {
+                let start_line = (rect.min.x as usize + j * texture_size.width as usize) * 4;
+                let end_line = (rect.max.x as usize + j * texture_size.width as usize) * 4;
+                patch_texture_data.extend_from_slice(&texture_data[start_line..end_line]);
+            }
+
+            // empty patches get a transparent pixel, textures can't be empty
+            let (size, patch_texture_data) = if rect.is_empty() {
+                (UVec2::ONE, vec![0; 4])
+            } else {
+                (rect.size(), patch_texture_data)
+            };
+            let patch_texture = Image::new(
+                Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: texture_size.depth_or_array_layers,
+                },
+                TextureDimension::D2,
+                patch_texture_data,
+                TextureFormat::Rgba8UnormSrgb,
+            );
+            textures_to_add.push(patch_texture);
+        }
+        Some(
+            textures_to_add
+                .into_iter()
+                .map(|patch_texture| textures.add(patch_texture))
+                .collect(),
+        )
     }
 }
 
 impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchBuilder<T> {
     /// Apply a `NinePatchBuilder` to a texture to get a `NinePatch` ready to be added to entities. This will split
-    /// the given texture according to the patches.
+    /// the given texture according to the patches, which must be valid for the texture, see
+    /// [`NinePatchBuilder::validate`].
     pub fn apply(
         &mut self,
         texture_handle: &Handle<Image>,
         textures: &mut Assets<Image>,
-    ) -> NinePatch<T> {
-        let (texture_size, texture_data) = {
-            let t = textures
-                .get(texture_handle)
-                .expect("could not get texture from handle");
-            (t.texture_descriptor.size, &t.data)
-        };
-        let mut textures_to_add = vec![];
+    ) -> Result<NinePatch<T>, NinePatchError> {
+        let texture_size = textures
+            .get(texture_handle)
+            .expect("could not get texture from handle")
+            .texture_descriptor
+            .size;
         if self.patch_textures.is_none() || self.original_texture.as_ref() != Some(texture_handle) {
-            let mut patch_textures = vec![];
-            let mut accu_y = 0;
-            for row in &self.patches {
-                let mut accu_x = 0;
-                for column_item in row {
-                    let start_x = accu_x;
-                    let end_x = accu_x + to_width(column_item.original_size, texture_size);
-
-                    let start_y = accu_y;
-                    let end_y = accu_y + to_height(column_item.original_size, texture_size);
-
-                    let mut patch_texture_data = vec![];
-                    for j in start_y as usize..end_y as usize {
-                        let start_line = (start_x as usize + j * texture_size.width as usize) * 4;
-                        let end_line = (end_x as usize + j * texture_size.width as usize) * 4;
-                        patch_texture_data.extend_from_slice(&texture_data[start_line..end_line]);
-                    }
-
-                    let patch_texture = Image::new(
-                        Extent3d {
-                            width: to_width(column_item.original_size, texture_size),
-                            height: to_height(column_item.original_size, texture_size),
-                            depth_or_array_layers: texture_size.depth_or_array_layers,
-                        },
-                        TextureDimension::D2,
-                        patch_texture_data,
-                        TextureFormat::Rgba8UnormSrgb,
-                    );
-                    textures_to_add.push(patch_texture);
-                    accu_x += to_width(column_item.original_size, texture_size);
-                }
-                accu_y += to_height(row[0].original_size, texture_size);
-            }
-            textures_to_add.into_iter().for_each(|patch_texture| {
-                let patch_texture_handle = textures.add(patch_texture);
-                patch_textures.push(patch_texture_handle);
-            });
-            self.patch_textures = Some(patch_textures);
+            // patches outside of the texture can't be split
+            self.validate(UVec2::new(texture_size.width, texture_size.height))?;
+            self.patch_textures = self.split(texture_handle, None, textures);
             self.original_texture = Some(texture_handle.clone());
         }
-        NinePatch {
-            patches: self.patches.clone(),
-            texture_size,
+        // columns are built as rows with their axes swapped
+        let (transposed, texture_size) = match self.grid {
+            PatchGrid::Rows => (None, UVec2::new(texture_size.width, texture_size.height)),
+            PatchGrid::Columns => (
+                Some(self.transposed()),
+                UVec2::new(texture_size.height, texture_size.width),
+            ),
+        };
+        let rows = transposed.as_ref().unwrap_or(self);
+        Ok(NinePatch {
+            patches: rows
+                .sized_patches(rows.area(texture_size).size())
+                .into_owned(),
+            grid: self.grid,
+            hidden_patches: rows.hidden_patches.clone(),
+            content_layouts: rows.content_layouts.clone(),
+            content_spans: rows.content_spans.clone(),
+            horizontal_fill: rows.horizontal_fill,
+            vertical_fill: rows.vertical_fill,
+            texture_size: extent(rows.area(texture_size).size()),
+            outset: self.border_outset(),
             // background: materials.add(Color::NONE.into()),
             splitted_texture: self.patch_textures.as_ref().cloned().unwrap(),
-        }
+        })
     }
 }
 
@@ -243,129 +1240,675 @@ pub struct NinePatchContent<T: Send + Sync + 'static> {
     pub parent: Entity,
 }
 
+/// Size in pixels of the fixed patches of a `NinePatch`, before any scaling
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct BorderExtent {
+    pub(crate) size: Vec2,
+    /// Axes on which the minimum size of the root node was set from the fixed patches
+    pub(crate) min_size: BVec2,
+}
+
+/// Size in pixels of a generated row or patch on the axis where it doesn't grow
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct BorderSize {
+    pub(crate) width: Option<f32>,
+    pub(crate) height: Option<f32>,
+}
+
+/// Component on each row of patches generated for a 9-Patch UI element, or each column for a grid of
+/// `PatchGrid::Columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct NinePatchRow {
+    /// Entity holding the `NinePatchData`
+    pub root: Entity,
+    /// Index of the row in `NinePatchBuilder::patches`
+    pub row: usize,
+}
+
+/// Component on each patch generated for a 9-Patch UI element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct NinePatchCell {
+    /// Entity holding the `NinePatchData`
+    pub root: Entity,
+    /// Row of the patch: its index in `NinePatchBuilder::patches` for a grid of rows, or in its column
+    pub row: usize,
+    /// Column of the patch: its index in its row for a grid of rows, or in `NinePatchBuilder::patches`
+    pub column: usize,
+}
+
+/// Mark an entity generated when building a `NinePatch`, with the entity holding the `NinePatchData`
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct GeneratedBy(pub(crate) Entity);
+
+/// Mark a patch that is not drawn
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct HiddenPatch;
+
+/// Position of the texture of a patch in the textures split for its `NinePatch`
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct PatchIndex(pub(crate) usize);
+
+/// Patches covered by a `ContentSpan`, used to place it over them
+#[derive(Debug, Clone, Component)]
+pub(crate) struct SpannedPatches(pub(crate) Vec<Entity>);
+
+/// Mark the node filling a 9-Patch UI element that holds its generated rows and layers
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct PatchContainer;
+
+/// Mark a node generated for a layer of a 9-Patch UI element, with the tint of the layer
+#[derive(Debug, Clone, Copy, Component)]
+pub(crate) struct PatchLayer {
+    pub(crate) tint: Color,
+}
+
+/// What the patches spawned for a `NinePatch` are used for
+enum PatchesRole<'a, T> {
+    /// Patches laying out the element and holding its content, not drawn when the element has layers
+    Grid {
+        contents: &'a Option<HashMap<T, Entity>>,
+        drawn: bool,
+    },
+    /// Patches of a layer, drawn below the content. Only the layer of the `NinePatchData` is animated
+    Layer { tint: Color, animated: bool },
+}
+
 /// `NinePatch` ready to be added to entities.
 #[derive(Debug)]
 pub struct NinePatch<T: Clone + Send + Sync + Eq + std::hash::Hash + 'static> {
+    /// Always rows, with their axes swapped for a grid of columns
     patches: Vec<Vec<Patch<T>>>,
+    grid: PatchGrid,
+    content_layouts: HashMap<T, ContentLayout>,
+    content_spans: Vec<ContentSpan<T>>,
+    hidden_patches: HashSet<(usize, usize)>,
+    horizontal_fill: FillMode,
+    vertical_fill: FillMode,
     texture_size: Extent3d,
+    /// Not transposed, as it applies to the node
+    outset: UiRect,
     // background: Handle<Image>,
     splitted_texture: Vec<Handle<Image>>,
 }
 impl<T: Clone + Send + Sync + Eq + std::hash::Hash + 'static> NinePatch<T> {
+    fn content_zone(
+        &self,
+        content: &T,
+        parent: Entity,
+    ) -> (NodeBundle, NinePatchContent<T>, GeneratedBy) {
+        let layout = self
+            .content_layouts
+            .get(content)
+            .copied()
+            .unwrap_or_default();
+        (
+            NodeBundle {
+                style: layout.style(),
+                background_color: BackgroundColor(Color::NONE),
+                focus_policy: FocusPolicy::Pass,
+                ..default()
+            },
+            NinePatchContent {
+                content: content.clone(),
+                loaded: false,
+                parent,
+            },
+            GeneratedBy(parent),
+        )
+    }
+
+    /// Smallest size the `NinePatch` can take without squashing its fixed patches: the widest row of fixed
+    /// patches, and the sum of the heights of the fixed rows
+    pub fn min_size(&self) -> Vec2 {
+        let min_size = min_size(&self.patches, self.texture_size);
+        match self.grid {
+            PatchGrid::Rows => min_size,
+            PatchGrid::Columns => min_size.yx(),
+        }
+    }
+
+    /// Are the patches drawn outside of the node
+    pub(crate) fn is_outset(&self) -> bool {
+        [
+            self.outset.left,
+            self.outset.right,
+            self.outset.top,
+            self.outset.bottom,
+        ]
+        .iter()
+        .any(|side| !matches!(side, Val::Px(0.) | Val::Percent(0.) | Val::Auto))
+    }
+
+    /// Border size of a node computed on the transposed grid, for a grid of columns
+    fn orient_border(&self, border_size: BorderSize) -> BorderSize {
+        match self.grid {
+            PatchGrid::Rows => border_size,
+            PatchGrid::Columns => BorderSize {
+                width: border_size.height,
+                height: border_size.width,
+            },
+        }
+    }
+
+    /// Style of a node computed on the transposed grid, for a grid of columns
+    fn orient(&self, style: Style) -> Style {
+        match self.grid {
+            PatchGrid::Rows => style,
+            PatchGrid::Columns => Style {
+                width: style.height,
+                height: style.width,
+                min_width: style.min_height,
+                min_height: style.min_width,
+                flex_direction: match style.flex_direction {
+                    FlexDirection::Row => FlexDirection::Column,
+                    FlexDirection::Column => FlexDirection::Row,
+                    FlexDirection::RowReverse => FlexDirection::ColumnReverse,
+                    FlexDirection::ColumnReverse => FlexDirection::RowReverse,
+                },
+                ..style
+            },
+        }
+    }
+
+    /// Build the patches in a container filling `parent`. Components of `parent` are kept, only the missing
+    /// components of a UI node are added. Returns the content zones by name, and the container
     pub(crate) fn add_with_parent(
         &self,
         commands: &mut Commands,
         parent: Entity,
         style: &Style,
-        contents: &Option<std::collections::HashMap<T, Entity>>,
-    ) {
-        commands.entity(parent).insert(NodeBundle {
-            style: Style {
-                flex_direction: FlexDirection::Column,
-                align_content: AlignContent::Stretch,
-                ..style.clone()
-            },
-            background_color: BackgroundColor(Color::NONE),
-            focus_policy: FocusPolicy::Pass,
-            ..default()
-        });
+        contents: &Option<HashMap<T, Entity>>,
+        shrink: NinePatchShrink,
+        drawn: bool,
+    ) -> (HashMap<T, Entity>, Entity) {
+        let min_size = self.min_size();
+        // the minimum size is only set where the user didn't set one
+        let min_size_set = BVec2::new(
+            shrink == NinePatchShrink::MinSize && style.min_width == Val::Auto,
+            shrink == NinePatchShrink::MinSize && style.min_height == Val::Auto,
+        );
+        commands
+            .entity(parent)
+            .insert(BorderExtent {
+                size: min_size,
+                min_size: min_size_set,
+            })
+            .add(move |mut root: EntityWorldMut| {
+                insert_missing_node_components(&mut root);
+                if let Some(mut style) = root.get_mut::<Style>() {
+                    if min_size_set.x {
+                        style.min_width = Val::Px(min_size.x);
+                    }
+                    if min_size_set.y {
+                        style.min_height = Val::Px(min_size.y);
+                    }
+                }
+            });
+        let container = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::ZERO,
+                        right: Val::ZERO,
+                        top: Val::ZERO,
+                        bottom: Val::ZERO,
+                        flex_direction: match self.grid {
+                            PatchGrid::Rows => FlexDirection::Column,
+                            PatchGrid::Columns => FlexDirection::Row,
+                        },
+                        align_content: AlignContent::Stretch,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::NONE),
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                },
+                GeneratedBy(parent),
+                PatchContainer,
+            ))
+            .id();
+        // below the children of the user
+        commands.entity(parent).insert_children(0, &[container]);
+        let (rows, grid, mut slots) =
+            self.spawn_rows(commands, parent, PatchesRole::Grid { contents, drawn });
+        commands.entity(container).push_children(&rows);
+
+        let grid = &grid;
+        for span in &self.content_spans {
+            let spanned: Option<Vec<Entity>> = span
+                .rows
+                .clone()
+                .flat_map(|row| {
+                    span.columns
+                        .clone()
+                        .map(move |column| grid.get(row).and_then(|row| row.get(column)).copied())
+                })
+                .collect();
+            let spanned = match spanned {
+                Some(spanned) if !spanned.is_empty() => spanned,
+                _ => {
+                    warn!(
+                        "content span covering rows {:?} and columns {:?} is outside of the patches",
+                        span.rows, span.columns
+                    );
+                    continue;
+                }
+            };
+            let (mut node, content, generated_by) = self.content_zone(&span.content, parent);
+            // actual position is set once the layout of the spanned patches is known
+            node.style.position_type = PositionType::Absolute;
+            node.style.flex_grow = 0.;
+            let mut content_zone =
+                commands.spawn((node, content, generated_by, SpannedPatches(spanned)));
+            if let Some(content_entity) = contents.as_ref().and_then(|m| m.get(&span.content)) {
+                content_zone.push_children(&[*content_entity]);
+            }
+            let id = content_zone.id();
+            slots.insert(span.content.clone(), id);
+            commands.entity(container).add_child(id);
+        }
+        (slots, container)
+    }
+
+    /// Spawn the rows of patches of the 9-Patch UI element `parent`, with the patches of each row and their
+    /// content zones by name
+    fn spawn_rows(
+        &self,
+        commands: &mut Commands,
+        parent: Entity,
+        role: PatchesRole<'_, T>,
+    ) -> (Vec<Entity>, Vec<Vec<Entity>>, HashMap<T, Entity>) {
         let mut rows = vec![];
+        let mut grid = vec![];
+        let mut slots = HashMap::new();
         let mut n = 0;
-        for row in self.patches.iter() {
-            let (size_height, growth) =
-                row.first()
-                    .map_or((Val::ZERO, 0.), |p| match p.target_height {
-                        Val::Px(i) => {
-                            let height = if i == 0. {
-                                Val::Px(to_height(p.original_size, self.texture_size) as f32)
-                            } else {
-                                Val::Px(i)
-                            };
-                            (height, 0.)
-                        }
-                        Val::Auto => (Val::Auto, 1.),
-                        Val::Percent(x) => (Val::Auto, x / 100.),
-                        other => (other, 0.),
-                    });
+        let row_growths = growths(self.patches.iter().map(|row| row_target(row)));
+        for ((row_index, row), growth) in self.patches.iter().enumerate().zip(row_growths) {
+            let size_height = row_height(row, self.texture_size);
+            let source = row
+                .first()
+                .map_or(0, |patch| to_height(patch, self.texture_size));
+            let (flex_basis, min_height) = flex_start(size_height, row_target(row), source);
 
-            let id = commands
-                .spawn(NodeBundle {
-                    style: Style {
+            let mut row_commands = commands.spawn((
+                NodeBundle {
+                    style: self.orient(Style {
                         width: Val::Percent(100.),
                         height: size_height,
+                        min_height,
                         flex_direction: FlexDirection::Row,
                         align_content: AlignContent::Stretch,
+                        flex_basis,
                         flex_grow: growth,
                         flex_shrink: growth,
                         ..default()
-                    },
+                    }),
                     background_color: BackgroundColor(Color::NONE),
                     focus_policy: FocusPolicy::Pass,
                     ..default()
-                })
-                .id();
+                },
+                GeneratedBy(parent),
+            ));
+            if let PatchesRole::Grid { .. } = role {
+                row_commands.insert(NinePatchRow {
+                    root: parent,
+                    row: row_index,
+                });
+            }
+            if let Val::Px(height) = size_height {
+                row_commands.insert(self.orient_border(BorderSize {
+                    width: None,
+                    height: Some(height),
+                }));
+            }
+            let id = row_commands.id();
             rows.push(id);
+            let mut row_patches = vec![];
+            let column_growths = growths(row.iter().map(|patch| patch.target_width));
             commands.entity(id).with_children(|row_parent| {
-                for column_item in row.iter() {
-                    let (size_width, growth) = match column_item.target_width {
-                        Val::Px(i) => {
-                            let width = if i == 0. {
-                                Val::Px(
-                                    to_width(column_item.original_size, self.texture_size) as f32
-                                )
-                            } else {
-                                Val::Px(i)
-                            };
-                            (width, 0.)
+                for ((column_index, column_item), growth) in
+                    row.iter().enumerate().zip(column_growths)
+                {
+                    let size_width = patch_width(column_item, self.texture_size);
+                    let (flex_basis, min_width) = flex_start(
+                        size_width,
+                        column_item.target_width,
+                        to_width(column_item, self.texture_size),
+                    );
+                    let size_height = patch_height(column_item, self.texture_size);
+                    let grows = BVec2::new(
+                        !matches!(size_width, Val::Px(_)),
+                        !matches!(size_height, Val::Px(_)),
+                    );
+                    let fill = |grows: bool, mode: FillMode| {
+                        if grows {
+                            mode
+                        } else {
+                            FillMode::Stretch
                         }
-                        Val::Auto => (Val::Auto, 1.),
-                        Val::Percent(x) => (Val::Auto, x / 100.),
-                        _ => todo!(),
                     };
-                    let size_height = match column_item.target_height {
-                        Val::Px(i) => {
-                            if i == 0. {
-                                Val::Px(
-                                    to_height(column_item.original_size, self.texture_size) as f32
-                                )
+                    let (horizontal, vertical) = (
+                        fill(grows.x, self.horizontal_fill),
+                        fill(grows.y, self.vertical_fill),
+                    );
+                    let hidden = self.hidden_patches.contains(&(row_index, column_index))
+                        || matches!(role, PatchesRole::Grid { drawn: false, .. });
+                    let tiled = !hidden
+                        && (horizontal != FillMode::Stretch || vertical != FillMode::Stretch);
+                    let mut child = row_parent.spawn((
+                        ImageBundle {
+                            image: UiImage {
+                                texture: match role {
+                                    PatchesRole::Grid { .. } => {
+                                        self.splitted_texture[n].clone_weak()
+                                    }
+                                    // the builder of a layer may split another texture later, and drop these
+                                    PatchesRole::Layer { .. } => self.splitted_texture[n].clone(),
+                                },
+                                ..default()
+                            },
+                            style: self.orient(Style {
+                                width: size_width,
+                                height: size_height,
+                                min_width,
+                                flex_basis,
+                                flex_grow: growth,
+                                flex_shrink: growth,
+                                ..default()
+                            }),
+                            // tiles are drawn instead of the image
+                            background_color: if tiled || hidden {
+                                BackgroundColor(Color::NONE)
                             } else {
-                                Val::Px(i)
-                            }
-                        }
-                        Val::Percent(_) => Val::Auto,
-                        other => other,
-                    };
-                    let mut child = row_parent.spawn(ImageBundle {
-                        image: UiImage {
-                            texture: self.splitted_texture[n].clone_weak(),
+                                BackgroundColor::DEFAULT
+                            },
+                            focus_policy: FocusPolicy::Pass,
                             ..default()
                         },
-                        style: Style {
-                            width: size_width,
-                            height: size_height,
-                            flex_grow: growth,
-                            flex_shrink: growth,
-                            ..default()
+                        GeneratedBy(parent),
+                    ));
+                    // the size of the image keeps its aspect ratio, patches are sized by their flex basis instead
+                    child.remove::<ContentSize>();
+                    match role {
+                        PatchesRole::Grid { .. } => {
+                            child.insert(match self.grid {
+                                PatchGrid::Rows => NinePatchCell {
+                                    root: parent,
+                                    row: row_index,
+                                    column: column_index,
+                                },
+                                PatchGrid::Columns => NinePatchCell {
+                                    root: parent,
+                                    row: column_index,
+                                    column: row_index,
+                                },
+                            });
+                        }
+                        PatchesRole::Layer { tint, .. } => {
+                            child.insert(PatchLayer { tint });
+                        }
+                    }
+                    // only patches drawn with the texture of the `NinePatchData` follow its animation
+                    if matches!(
+                        role,
+                        PatchesRole::Grid { drawn: true, .. }
+                            | PatchesRole::Layer { animated: true, .. }
+                    ) {
+                        child.insert(PatchIndex(n));
+                    }
+                    let border_size = BorderSize {
+                        width: match size_width {
+                            Val::Px(width) => Some(width),
+                            _ => None,
                         },
-                        focus_policy: FocusPolicy::Pass,
-                        ..default()
-                    });
-                    if let Some(content_part) = column_item.content.as_ref() {
-                        child.insert(NinePatchContent {
-                            content: content_part.clone(),
-                            loaded: false,
-                            parent,
+                        height: match size_height {
+                            Val::Px(height) => Some(height),
+                            _ => None,
+                        },
+                    };
+                    if border_size.width.is_some() || border_size.height.is_some() {
+                        child.insert(self.orient_border(border_size));
+                    }
+                    if hidden {
+                        child.insert(HiddenPatch);
+                    }
+                    if tiled {
+                        let size = Vec2::new(
+                            to_width(column_item, self.texture_size) as f32,
+                            to_height(column_item, self.texture_size) as f32,
+                        );
+                        child.insert(match self.grid {
+                            PatchGrid::Rows => TiledPatch {
+                                texture: self.splitted_texture[n].clone_weak(),
+                                size,
+                                horizontal,
+                                vertical,
+                                grows,
+                                tiles: None,
+                            },
+                            PatchGrid::Columns => TiledPatch {
+                                texture: self.splitted_texture[n].clone_weak(),
+                                size: size.yx(),
+                                horizontal: vertical,
+                                vertical: horizontal,
+                                grows: BVec2::new(grows.y, grows.x),
+                                tiles: None,
+                            },
+                        });
+                    }
+                    let content = match role {
+                        PatchesRole::Grid { contents, .. } => column_item
+                            .content
+                            .as_ref()
+                            .map(|content| (content, contents)),
+                        PatchesRole::Layer { .. } => None,
+                    };
+                    if let Some((content_part, contents)) = content {
+                        child.with_children(|patch| {
+                            let mut content_zone =
+                                patch.spawn(self.content_zone(content_part, parent));
+                            if let Some(content_entity) =
+                                contents.as_ref().and_then(|m| m.get(content_part))
+                            {
+                                content_zone.push_children(&[*content_entity]);
+                            }
+                            slots.insert(content_part.clone(), content_zone.id());
                         });
-                        if let Some(content_entity) =
-                            contents.as_ref().and_then(|m| m.get(content_part))
-                        {
-                            child.push_children(&[*content_entity]);
-                        }
                     }
+                    row_patches.push(child.id());
                     n += 1;
                 }
             });
+            grid.push(row_patches);
         }
-        commands.entity(parent).push_children(&rows);
+        (rows, grid, slots)
+    }
+
+    /// Spawn the patches as a layer of the 9-Patch UI element `parent`, in a node covering it and its outset. The node
+    /// is not added to `parent`
+    pub(crate) fn add_layer(
+        &self,
+        commands: &mut Commands,
+        parent: Entity,
+        tint: Color,
+        animated: bool,
+    ) -> Entity {
+        let (rows, _, _) = self.spawn_rows(commands, parent, PatchesRole::Layer { tint, animated });
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        // the outset extends the layer beyond the node
+                        left: outside(self.outset.left),
+                        right: outside(self.outset.right),
+                        top: outside(self.outset.top),
+                        bottom: outside(self.outset.bottom),
+                        flex_direction: match self.grid {
+                            PatchGrid::Rows => FlexDirection::Column,
+                            PatchGrid::Columns => FlexDirection::Row,
+                        },
+                        align_content: AlignContent::Stretch,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::NONE),
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                },
+                GeneratedBy(parent),
+                PatchLayer { tint },
+            ))
+            .push_children(&rows)
+            .id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, ui::FocusPolicy};
+
+    use crate::{
+        testing, NinePatchBuilder, NinePatchCell, NinePatchRow, Patch, PatchSize, PatchTarget,
+    };
+
+    #[test]
+    fn generated_nodes_follow_the_layout() {
+        let row = |target_height| {
+            [
+                PatchTarget::Source,
+                PatchTarget::Percent(50.),
+                PatchTarget::Grow(1.),
+                PatchTarget::Grow(3.),
+            ]
+            .map(|target_width| Patch {
+                original_width: PatchSize::Fixed(10),
+                original_height: PatchSize::Fixed(10),
+                target_width,
+                target_height,
+                content: None,
+            })
+            .to_vec()
+        };
+        let builder = NinePatchBuilder::from_patches(vec![
+            row(PatchTarget::Source),
+            row(PatchTarget::Percent(50.)),
+            row(PatchTarget::Grow(1.)),
+        ]);
+        let (texture_size, size) = (UVec2::new(40, 30), Vec2::new(270., 150.));
+        let layout = builder.layout(texture_size, size);
+        let mut app = testing::app();
+        let root = testing::spawn(&mut app, builder, texture_size, size);
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let mut rows_query = app.world.query::<(&NinePatchRow, &Style)>();
+        let percent_row = rows_query
+            .iter(&app.world)
+            .find(|(row, _)| row.root == root && row.row == 1)
+            .map(|(_, style)| style)
+            .unwrap();
+        assert_eq!(percent_row.flex_basis, Val::Px(0.));
+        assert_eq!(percent_row.min_height, Val::Px(0.));
+        assert_eq!(percent_row.flex_grow, 0.5);
+
+        let mut cells_query = app.world.query::<(&NinePatchCell, &Style, &Node)>();
+        let mut cells = 0;
+        for (cell, style, node) in cells_query.iter(&app.world) {
+            if cell.root != root {
+                continue;
+            }
+            cells += 1;
+            if cell.column == 1 {
+                assert_eq!(style.flex_basis, Val::Px(0.));
+                assert_eq!(style.min_width, Val::Px(0.));
+                assert_eq!(style.flex_grow, 0.5);
+            }
+            assert_eq!(
+                node.size(),
+                layout[cell.row][cell.column].size(),
+                "patch {cell:?}"
+            );
+        }
+        assert_eq!(cells, 12);
+        assert_eq!(
+            layout[1][1].size(),
+            Vec2::new(120., 65.),
+            "half of the free space on both axes"
+        );
+    }
+
+    #[test]
+    fn margin_fractions_cover_the_texture() {
+        let builder = NinePatchBuilder::<()>::by_margin_fractions(0.25, 0.5, 0.25, 0.25);
+        assert!(builder.border_image.is_none());
+        assert_eq!(
+            builder.patches[1][1].original_width,
+            PatchSize::RemainingFractions(0.25, 0.25)
+        );
+        assert_eq!(
+            builder.patches[2][0].original_height,
+            PatchSize::Fraction(0.5)
+        );
+
+        // margins are rounded up on both sides, the middle takes what remains
+        let texture_size = UVec2::new(10, 10);
+        assert!(builder.validate(texture_size).is_ok());
+        let rects = builder.resolve(texture_size);
+        assert_eq!(rects[0][0], URect::new(0, 0, 3, 3));
+        assert_eq!(rects[1][1], URect::new(3, 3, 7, 5));
+        assert_eq!(rects[2][2], URect::new(7, 5, 10, 10));
+
+        let invalid = NinePatchBuilder::<()>::by_margin_fractions(0.25, 0.25, 0.75, 0.5);
+        assert!(invalid.validate(texture_size).is_err());
+    }
+
+    #[test]
+    fn components_of_the_root_are_kept() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(10, 10, 10, 10),
+            UVec2::new(30, 30),
+            Vec2::new(100., 100.),
+        );
+        app.world.entity_mut(root).insert((
+            BackgroundColor(Color::RED),
+            FocusPolicy::Block,
+            ZIndex::Global(5),
+        ));
+        {
+            let mut style = app.world.get_mut::<Style>(root).unwrap();
+            style.margin = UiRect::all(Val::Px(20.));
+            style.flex_direction = FlexDirection::Column;
+        }
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let style = app.world.get::<Style>(root).unwrap();
+        assert_eq!(style.width, Val::Px(100.));
+        assert_eq!(style.margin, UiRect::all(Val::Px(20.)));
+        assert_eq!(style.flex_direction, FlexDirection::Column);
+        assert_eq!(
+            app.world.get::<BackgroundColor>(root).unwrap().0,
+            Color::RED
+        );
+        assert_eq!(
+            app.world.get::<FocusPolicy>(root),
+            Some(&FocusPolicy::Block)
+        );
+        assert!(matches!(
+            app.world.get::<ZIndex>(root),
+            Some(ZIndex::Global(5))
+        ));
+        // the components the user didn't add are added for the layout
+        assert_eq!(
+            app.world.get::<Node>(root).unwrap().size(),
+            Vec2::new(100., 100.)
+        );
+        assert!(app.world.get::<Visibility>(root).is_some());
+        assert!(app.world.get::<BorderColor>(root).is_some());
     }
 }
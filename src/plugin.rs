@@ -1,6 +1,15 @@
-use bevy::prelude::*;
+use std::collections::HashSet;
 
-use crate::ninepatch::*;
+use bevy::{prelude::*, ui::UiSystem};
+
+use crate::{
+    animation::animate_ninepatches,
+    content::{ContentPolicy, NinePatchReady, NinePatchSlots},
+    fill::{tile_patches, TiledPatch},
+    focus::{focus_ninepatches, ignore_transparent_pixels},
+    ninepatch::*,
+    tween::{tween_ninepatches, NinePatchTweenCompleted},
+};
 
 /// State of the current `NinePatch`
 #[derive(Debug, Clone, Component)]
@@ -13,6 +22,18 @@ pub struct NinePatchData<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePat
     pub loaded: bool,
     /// Entity that should be used for the content
     pub content: Option<std::collections::HashMap<T, Entity>>,
+    /// How the element behaves when it is smaller than its fixed patches
+    pub shrink: NinePatchShrink,
+}
+
+/// How a `NinePatch` behaves when its node is smaller than the sum of its fixed patches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NinePatchShrink {
+    /// Set a minimum size on the node so that fixed patches are never squashed or clipped
+    #[default]
+    MinSize,
+    /// Scale all fixed patches down proportionally to fit in the available space, like CSS `border-image`
+    ScaleBorders,
 }
 
 impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> Default
@@ -24,6 +45,7 @@ impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> Default
             nine_patch: Default::default(),
             loaded: false,
             content: Default::default(),
+            shrink: Default::default(),
         }
     }
 }
@@ -44,10 +66,67 @@ impl<T: Clone + Send + Sync + Default + Eq + std::hash::Hash + TypePath + 'stati
             nine_patch,
             loaded: false,
             content: Some(content_map),
+            shrink: Default::default(),
         }
     }
 }
 
+/// Color multiplied with the texture of all the patches of a 9-Patch UI element, added on the entity holding the
+/// `NinePatchData`
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct NinePatchTint(pub Color);
+
+/// A layer of a 9-Patch UI element, drawn from its own texture and `NinePatchBuilder`
+#[derive(Debug, Clone)]
+pub struct NinePatchLayer<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> {
+    /// Handle of the texture
+    pub texture: Handle<Image>,
+    /// Handle to the `NinePatchBuilder`. Its content zones are ignored
+    pub nine_patch: Handle<NinePatchBuilder<T>>,
+    /// Color multiplied with the texture of the layer, on top of the `NinePatchTint` of the element
+    pub tint: Color,
+    /// Layers are drawn from the lowest `z` to the highest. The `NinePatchData` is at 0, before other layers at 0
+    pub z: i32,
+}
+
+impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> NinePatchLayer<T> {
+    /// Create an untinted layer
+    pub fn new(texture: Handle<Image>, nine_patch: Handle<NinePatchBuilder<T>>, z: i32) -> Self {
+        NinePatchLayer {
+            texture,
+            nine_patch,
+            tint: Color::WHITE,
+            z,
+        }
+    }
+
+    /// Set the tint of the layer
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+}
+
+/// Layers stacked in a 9-Patch UI element, added on the entity holding the `NinePatchData` before it is loaded. All
+/// layers cover the element, which is laid out by the `NinePatchData`, and its content is placed above them
+#[derive(Debug, Clone, Component)]
+pub struct NinePatchLayers<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static>(
+    pub Vec<NinePatchLayer<T>>,
+);
+
+/// Scale of the fixed patches of a 9-Patch UI element, added on the entity holding the `NinePatchData`. With
+/// `NinePatchShrink::MinSize`, the minimum size of the element is scaled too
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct NinePatchBorderScale(pub f32);
+
+/// What happens to the generated nodes of a 9-Patch UI element when its `NinePatchData` is removed, or its entity
+/// despawned without its descendants
+#[derive(Debug, Clone, Copy, Default, Resource)]
+pub struct NinePatchCleanup {
+    /// What to do with entities placed in content zones, detached by default. Generated nodes are always despawned
+    pub content: ContentPolicy,
+}
+
 /// Plugin that will add the system and the resource for nine patch
 #[derive(Debug, Clone, Copy)]
 pub struct NinePatchPlugin<T: Clone + Send + Sync + 'static = ()> {
@@ -66,7 +145,45 @@ impl<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static> Plugin
 {
     fn build(&self, app: &mut App) {
         app.init_asset::<NinePatchBuilder<T>>()
-            .add_systems(Update, create_ninepatches::<T>);
+            .add_event::<NinePatchReady<T>>()
+            .add_event::<NinePatchTweenCompleted>()
+            .init_resource::<NinePatchCleanup>()
+            .add_systems(
+                Update,
+                (
+                    create_ninepatches::<T>,
+                    scale_borders::<T>.after(tween_ninepatches::<T>),
+                    // nodes of a replaced `NinePatchData` are despawned before the new ones are built
+                    cleanup_ninepatches::<T>.before(create_ninepatches::<T>),
+                    tint_patches.after(tween_ninepatches::<T>),
+                    tween_ninepatches::<T>,
+                    animate_ninepatches::<T>.after(create_ninepatches::<T>),
+                ),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    focus_ninepatches.before(UiSystem::Focus),
+                    ignore_transparent_pixels.after(UiSystem::Focus),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                (place_content_spans, tile_patches).after(UiSystem::Layout),
+            );
+    }
+}
+
+/// Register `NinePatchBuilder<String>` assets for the loaders of nine patches named by strings, if the app doesn't
+/// already have them
+#[cfg(any(feature = "aseprite", feature = "godot"))]
+pub(crate) fn init_string_builders(app: &mut App) {
+    // builders are usually registered by `NinePatchPlugin::<String>`
+    if !app
+        .world
+        .contains_resource::<Assets<NinePatchBuilder<String>>>()
+    {
+        app.init_asset::<NinePatchBuilder<String>>();
     }
 }
 
@@ -75,19 +192,673 @@ fn create_ninepatches<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath +
     mut commands: Commands,
     mut nine_patches: ResMut<Assets<NinePatchBuilder<T>>>,
     mut textures: ResMut<Assets<Image>>,
-    mut patches_query: Query<(Entity, &mut NinePatchData<T>, &Style)>,
+    mut patches_query: Query<(
+        Entity,
+        &mut NinePatchData<T>,
+        &Style,
+        Option<&NinePatchLayers<T>>,
+    )>,
+    mut ready_events: EventWriter<NinePatchReady<T>>,
+    mut invalid: Local<HashSet<Entity>>,
 ) {
-    for (entity, mut data, style) in patches_query.iter_mut() {
+    for (entity, mut data, style, layers) in patches_query.iter_mut() {
         if !data.loaded {
+            let layers = layers.map_or(&[][..], |layers| &layers.0[..]);
+            let layers_loaded = layers.iter().all(|layer| {
+                nine_patches.contains(&layer.nine_patch) && textures.contains(&layer.texture)
+            });
+            if !layers_loaded {
+                // layers are not available yet, will try next loop
+                continue;
+            }
             if let Some(nine_patch) = nine_patches.get_mut(&data.nine_patch) {
                 if textures.get(&data.texture).is_none() {
                     // texture is not available yet, will try next loop
                     continue;
                 }
-                let np = nine_patch.apply(&data.texture, &mut textures);
-                np.add_with_parent(&mut commands, entity, style, &data.content);
+                let np = match nine_patch.apply(&data.texture, &mut textures) {
+                    Ok(np) => np,
+                    Err(error) => {
+                        // stays not loaded, warned once
+                        if invalid.insert(entity) {
+                            warn!("invalid nine patch: {error}");
+                        }
+                        continue;
+                    }
+                };
+                invalid.remove(&entity);
+                let (slots, container) = np.add_with_parent(
+                    &mut commands,
+                    entity,
+                    style,
+                    &data.content,
+                    data.shrink,
+                    layers.is_empty() && !np.is_outset(),
+                );
+                if !layers.is_empty() || np.is_outset() {
+                    // the `NinePatchData` is drawn as a layer too, below the patches holding the content and
+                    // possibly outside of them
+                    let mut stack =
+                        vec![(0, np.add_layer(&mut commands, entity, Color::WHITE, true))];
+                    for layer in layers {
+                        let Some(builder) = nine_patches.get_mut(&layer.nine_patch) else {
+                            continue;
+                        };
+                        let layer_np = match builder.apply(&layer.texture, &mut textures) {
+                            Ok(layer_np) => layer_np,
+                            Err(error) => {
+                                warn!("invalid nine patch layer, it is not drawn: {error}");
+                                continue;
+                            }
+                        };
+                        stack.push((
+                            layer.z,
+                            layer_np.add_layer(&mut commands, entity, layer.tint, false),
+                        ));
+                    }
+                    stack.sort_by_key(|(z, _)| *z);
+                    let stack: Vec<Entity> = stack.into_iter().map(|(_, layer)| layer).collect();
+                    commands.entity(container).insert_children(0, &stack);
+                }
+                commands.entity(entity).insert(NinePatchSlots {
+                    slots: slots.clone(),
+                });
+                ready_events.send(NinePatchReady { entity, slots });
                 data.loaded = true;
             }
         }
     }
 }
+
+fn cleanup_ninepatches<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static>(
+    mut commands: Commands,
+    mut removed: RemovedComponents<NinePatchData<T>>,
+    cleanup: Res<NinePatchCleanup>,
+    generated_query: Query<(Entity, &GeneratedBy, Option<&Parent>)>,
+    content_query: Query<&Children, With<NinePatchContent<T>>>,
+    mut roots_query: Query<(&BorderExtent, &mut Style)>,
+) {
+    let removed: HashSet<Entity> = removed.read().collect();
+    if removed.is_empty() {
+        return;
+    }
+
+    // content is handled first, before the generated nodes holding it are despawned
+    for (entity, generated_by, _) in generated_query.iter() {
+        if !removed.contains(&generated_by.0) {
+            continue;
+        }
+        let contents = content_query.get(entity).into_iter().flatten();
+        for content in contents.filter(|content| !generated_query.contains(**content)) {
+            match cleanup.content {
+                ContentPolicy::Despawn => commands.entity(*content).despawn_recursive(),
+                ContentPolicy::Detach => {
+                    commands.entity(*content).remove_parent();
+                }
+            }
+        }
+    }
+
+    // only the top most generated nodes need to be despawned, their descendants will follow
+    for (entity, generated_by, parent) in generated_query.iter() {
+        if !removed.contains(&generated_by.0) {
+            continue;
+        }
+        let parent_generated = parent
+            .and_then(|parent| generated_query.get(parent.get()).ok())
+            .is_some_and(|(_, parent_generated_by, _)| parent_generated_by.0 == generated_by.0);
+        if !parent_generated {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    for root in removed {
+        // the minimum size set from the fixed patches is removed with them
+        if let Ok((extent, mut style)) = roots_query.get_mut(root) {
+            if extent.min_size.x {
+                style.min_width = Val::Auto;
+            }
+            if extent.min_size.y {
+                style.min_height = Val::Auto;
+            }
+        }
+        if let Some(mut root) = commands.get_entity(root) {
+            root.remove::<(NinePatchSlots<T>, BorderExtent)>();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn scale_borders<T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static>(
+    mut roots_query: Query<
+        (
+            Entity,
+            &NinePatchData<T>,
+            Ref<Node>,
+            Ref<BorderExtent>,
+            Option<Ref<NinePatchBorderScale>>,
+            &Children,
+            &mut Style,
+        ),
+        Without<BorderSize>,
+    >,
+    mut removed: RemovedComponents<NinePatchBorderScale>,
+    children_query: Query<&Children>,
+    containers_query: Query<&Children, With<PatchContainer>>,
+    layers_query: Query<&Children, With<PatchLayer>>,
+    mut borders_query: Query<(&BorderSize, &mut Style), Without<BorderExtent>>,
+) {
+    let removed: HashSet<Entity> = removed.read().collect();
+    for (entity, data, node, extent, border_scale, children, mut root_style) in
+        roots_query.iter_mut()
+    {
+        let scale_changed = border_scale
+            .as_ref()
+            .is_some_and(|scale| scale.is_changed())
+            || removed.contains(&entity);
+        let fit = data.shrink == NinePatchShrink::ScaleBorders && node.is_changed();
+        if !(scale_changed || fit || extent.is_added()) {
+            continue;
+        }
+        let border_scale = border_scale.map_or(1., |scale| scale.0);
+        let mut scale = border_scale;
+        if data.shrink == NinePatchShrink::ScaleBorders {
+            let size = node.size();
+            scale *= [(size.x, extent.size.x), (size.y, extent.size.y)]
+                .into_iter()
+                .filter(|(_, border)| *border * border_scale > 0.)
+                .map(|(available, border)| available / (border * border_scale))
+                .fold(1., f32::min);
+        }
+
+        // the minimum size follows the fixed patches when it was set from them
+        let min_size = extent.size * border_scale;
+        if extent.min_size.x && root_style.min_width != Val::Px(min_size.x) {
+            root_style.min_width = Val::Px(min_size.x);
+        }
+        if extent.min_size.y && root_style.min_height != Val::Px(min_size.y) {
+            root_style.min_height = Val::Px(min_size.y);
+        }
+
+        // only rows, and patches inside them, are generated by the plugin in its container. Content placed
+        // deeper can be another `NinePatch` that must not be affected. Layers hold their own rows
+        let rows: Vec<Entity> = children
+            .iter()
+            .filter_map(|child| containers_query.get(*child).ok())
+            .flatten()
+            .copied()
+            .collect();
+        let layer_rows = rows
+            .iter()
+            .filter_map(|layer| layers_query.get(*layer).ok())
+            .flatten();
+        let patches = rows
+            .iter()
+            .chain(layer_rows)
+            .flat_map(|row| children_query.get(*row).into_iter().flatten())
+            .chain(rows.iter());
+        for entity in patches {
+            if let Ok((border_size, mut style)) = borders_query.get_mut(*entity) {
+                if let Some(width) = border_size.width {
+                    let width = Val::Px(width * scale);
+                    if style.width != width {
+                        style.width = width;
+                    }
+                }
+                if let Some(height) = border_size.height {
+                    let height = Val::Px(height * scale);
+                    if style.height != height {
+                        style.height = height;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn tint_patches(
+    tints_query: Query<Ref<NinePatchTint>>,
+    mut removed: RemovedComponents<NinePatchTint>,
+    mut images_query: Query<
+        (Ref<GeneratedBy>, &mut BackgroundColor, Option<&PatchLayer>),
+        (With<UiImage>, Without<TiledPatch>, Without<HiddenPatch>),
+    >,
+) {
+    let removed: HashSet<Entity> = removed.read().collect();
+    for (generated_by, mut color, layer) in images_query.iter_mut() {
+        let tint = tints_query.get(generated_by.0).ok();
+        let changed = generated_by.is_added()
+            || removed.contains(&generated_by.0)
+            || tint.as_ref().is_some_and(|tint| tint.is_changed());
+        if !changed {
+            continue;
+        }
+        let tint = layer_tint(tint.map_or(Color::WHITE, |tint| tint.0), layer);
+        if color.0 != tint {
+            color.0 = tint;
+        }
+    }
+}
+
+/// Tint of a patch, from the tint of its 9-Patch UI element and of its layer
+pub(crate) fn layer_tint(tint: Color, layer: Option<&PatchLayer>) -> Color {
+    match layer {
+        Some(layer) => tint * Vec4::from(layer.tint.as_rgba_f32()),
+        None => tint,
+    }
+}
+
+/// Rectangle of a node, relative to the top left corner of one of its ancestors
+fn rect_in_ancestor(
+    entity: Entity,
+    ancestor: Entity,
+    ancestor_size: Vec2,
+    nodes_query: &Query<(&Node, &Transform, Option<&Parent>)>,
+) -> Option<Rect> {
+    let (node, transform, mut parent) = nodes_query.get(entity).ok()?;
+    // UI transforms are relative to the center of the parent node
+    let mut center = transform.translation.truncate();
+    while let Some(current) = parent {
+        if current.get() == ancestor {
+            return Some(Rect::from_center_size(
+                center + ancestor_size / 2.,
+                node.size(),
+            ));
+        }
+        let (_, transform, next) = nodes_query.get(current.get()).ok()?;
+        center += transform.translation.truncate();
+        parent = next;
+    }
+    None
+}
+
+/// Place content spans over their patches, once the patches are laid out. The new position and size of a span are
+/// only laid out in the next frame, so spans follow a resize of their element one frame late
+fn place_content_spans(
+    mut spans_query: Query<(&SpannedPatches, &Parent, &mut Style)>,
+    nodes_query: Query<(&Node, &Transform, Option<&Parent>)>,
+) {
+    for (spanned, parent, mut style) in spans_query.iter_mut() {
+        let Ok((parent_node, ..)) = nodes_query.get(parent.get()) else {
+            continue;
+        };
+        let Some(rect) = spanned
+            .0
+            .iter()
+            .filter_map(|patch| {
+                rect_in_ancestor(*patch, parent.get(), parent_node.size(), &nodes_query)
+            })
+            .reduce(|a, b| a.union(b))
+        else {
+            continue;
+        };
+        let (left, top) = (Val::Px(rect.min.x), Val::Px(rect.min.y));
+        let (width, height) = (Val::Px(rect.width()), Val::Px(rect.height()));
+        if style.left != left || style.top != top || style.width != width || style.height != height
+        {
+            style.left = left;
+            style.top = top;
+            style.width = width;
+            style.height = height;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        ninepatch::{PatchContainer, PatchLayer},
+        testing, ContentPolicy, ContentSpan, NinePatchBuilder, NinePatchCell, NinePatchCleanup,
+        NinePatchData, NinePatchLayer, NinePatchLayers, NinePatchRow, NinePatchShrink,
+        NinePatchSlots,
+    };
+
+    fn cells(app: &mut App, root: Entity) -> usize {
+        let mut cells_query = app.world.query::<&NinePatchCell>();
+        cells_query
+            .iter(&app.world)
+            .filter(|cell| cell.root == root)
+            .count()
+    }
+
+    #[test]
+    fn patches_outside_of_texture_are_not_built() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(40, 10, 10, 10),
+            UVec2::new(30, 30),
+            Vec2::new(100., 100.),
+        );
+        app.update();
+        app.update();
+
+        let data = app.world.get::<NinePatchData<()>>(root).unwrap();
+        assert!(!data.loaded);
+        assert!(app.world.get::<Children>(root).is_none());
+    }
+
+    #[test]
+    fn min_size_is_set_from_fixed_patches() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(5, 10, 8, 12),
+            UVec2::new(40, 40),
+            Vec2::new(100., 100.),
+        );
+        app.update();
+        app.update();
+
+        let style = app.world.get::<Style>(root).unwrap();
+        assert_eq!(style.min_width, Val::Px(20.));
+        assert_eq!(style.min_height, Val::Px(15.));
+    }
+
+    #[test]
+    fn min_size_set_by_the_user_is_kept() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(5, 10, 8, 12),
+            UVec2::new(40, 40),
+            Vec2::new(100., 100.),
+        );
+        app.world.get_mut::<Style>(root).unwrap().min_width = Val::Px(50.);
+        app.update();
+        app.update();
+
+        let style = app.world.get::<Style>(root).unwrap();
+        assert_eq!(style.min_width, Val::Px(50.));
+        assert_eq!(style.min_height, Val::Px(15.));
+    }
+
+    #[test]
+    fn borders_are_scaled_down_in_a_smaller_node() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(10, 10, 10, 30),
+            UVec2::new(60, 60),
+            Vec2::new(20., 40.),
+        );
+        app.world.get_mut::<NinePatchData<()>>(root).unwrap().shrink =
+            NinePatchShrink::ScaleBorders;
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let style = app.world.get::<Style>(root).unwrap();
+        assert_eq!(style.min_width, Val::Auto);
+        assert_eq!(style.min_height, Val::Auto);
+        // the 40 pixels of fixed width are scaled to the 20 pixels of the node, on both axes
+        let mut cells_query = app.world.query::<(&NinePatchCell, &Style)>();
+        let corners: Vec<_> = cells_query
+            .iter(&app.world)
+            .filter(|(cell, _)| cell.root == root && cell.row == 0 && cell.column != 1)
+            .map(|(cell, style)| (cell.column, style.width, style.height))
+            .collect();
+        assert_eq!(corners.len(), 2);
+        for (column, width, height) in corners {
+            let expected = if column == 0 { 5. } else { 15. };
+            assert_eq!(width, Val::Px(expected));
+            assert_eq!(height, Val::Px(5.));
+        }
+    }
+
+    #[test]
+    fn content_spans_follow_a_resize_one_frame_late() {
+        let mut app = testing::app();
+        let builder = NinePatchBuilder::by_margins(10, 10, 10, 10).with_content_span(ContentSpan {
+            content: (),
+            rows: 0..2,
+            columns: 0..2,
+        });
+        let root = testing::spawn(&mut app, builder, UVec2::new(30, 30), Vec2::new(100., 100.));
+        for _ in 0..4 {
+            app.update();
+        }
+        let span = app
+            .world
+            .get::<NinePatchSlots<()>>(root)
+            .unwrap()
+            .get(&())
+            .unwrap();
+        assert_eq!(
+            app.world.get::<Node>(span).unwrap().size(),
+            Vec2::new(90., 90.)
+        );
+
+        app.world.get_mut::<Style>(root).unwrap().width = Val::Px(200.);
+        app.update();
+        // the patches are laid out at the new size, the span is placed over them in the next layout
+        assert_eq!(app.world.get::<Style>(span).unwrap().width, Val::Px(190.));
+        assert_eq!(
+            app.world.get::<Node>(span).unwrap().size(),
+            Vec2::new(90., 90.)
+        );
+        app.update();
+        assert_eq!(
+            app.world.get::<Node>(span).unwrap().size(),
+            Vec2::new(190., 90.)
+        );
+    }
+
+    #[test]
+    fn replaced_data_is_cleaned_up_before_the_new_build() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(10, 10, 10, 10),
+            UVec2::new(30, 30),
+            Vec2::new(100., 100.),
+        );
+        app.update();
+        app.update();
+        assert_eq!(cells(&mut app, root), 9);
+
+        let texture = testing::texture(&mut app, UVec2::new(30, 30), [255; 4]);
+        let nine_patch = app
+            .world
+            .resource_mut::<Assets<NinePatchBuilder>>()
+            .add(NinePatchBuilder::by_margins(5, 5, 5, 5));
+        app.world
+            .entity_mut(root)
+            .remove::<NinePatchData<()>>()
+            .insert(NinePatchData::<()> {
+                texture,
+                nine_patch,
+                ..default()
+            });
+        app.update();
+        app.update();
+
+        assert!(app.world.get::<NinePatchData<()>>(root).unwrap().loaded);
+        assert!(app.world.get::<NinePatchSlots<()>>(root).is_some());
+        assert_eq!(cells(&mut app, root), 9);
+        assert_eq!(
+            app.world.get::<Style>(root).unwrap().min_width,
+            Val::Px(10.)
+        );
+    }
+
+    #[test]
+    fn removed_data_is_cleaned_up_with_the_content_policy() {
+        for policy in [ContentPolicy::Despawn, ContentPolicy::Detach] {
+            let mut app = testing::app();
+            app.insert_resource(NinePatchCleanup { content: policy });
+            let root = testing::spawn(
+                &mut app,
+                NinePatchBuilder::by_margins(10, 10, 10, 10),
+                UVec2::new(30, 30),
+                Vec2::new(100., 100.),
+            );
+            let content = app.world.spawn(NodeBundle::default()).id();
+            let mut data = app.world.get_mut::<NinePatchData<()>>(root).unwrap();
+            data.content = Some([((), content)].into());
+            app.world.get_mut::<Style>(root).unwrap().min_height = Val::Px(50.);
+            app.update();
+            app.update();
+            assert!(app.world.get::<Parent>(content).is_some());
+
+            app.world.entity_mut(root).remove::<NinePatchData<()>>();
+            app.update();
+
+            assert_eq!(cells(&mut app, root), 0);
+            assert!(app
+                .world
+                .get::<Children>(root)
+                .is_none_or(|children| children.is_empty()));
+            assert!(app.world.get::<NinePatchSlots<()>>(root).is_none());
+            match policy {
+                ContentPolicy::Despawn => assert!(app.world.get_entity(content).is_none()),
+                ContentPolicy::Detach => assert!(app.world.get::<Parent>(content).is_none()),
+            }
+            // only the minimum size set by the plugin is reset
+            let style = app.world.get::<Style>(root).unwrap();
+            assert_eq!(style.min_width, Val::Auto);
+            assert_eq!(style.min_height, Val::Px(50.));
+        }
+    }
+
+    #[test]
+    fn content_is_detached_by_default() {
+        assert_eq!(NinePatchCleanup::default().content, ContentPolicy::Detach);
+        assert_eq!(ContentPolicy::default(), ContentPolicy::Detach);
+    }
+
+    #[test]
+    fn layers_are_stacked_by_z_and_tinted() {
+        let mut app = testing::app();
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(10, 10, 10, 10),
+            UVec2::new(30, 30),
+            Vec2::new(100., 100.),
+        );
+        let mut layer = |builder: NinePatchBuilder, z, tint| {
+            let texture = testing::texture(&mut app, UVec2::new(30, 30), [255; 4]);
+            let nine_patch = app
+                .world
+                .resource_mut::<Assets<NinePatchBuilder>>()
+                .add(builder);
+            NinePatchLayer::new(texture, nine_patch, z).with_tint(tint)
+        };
+        let margins = || NinePatchBuilder::by_margins(10, 10, 10, 10);
+        let layers = NinePatchLayers(vec![
+            layer(margins(), 1, Color::BLUE),
+            layer(margins(), 0, Color::GREEN),
+            // patches outside of the texture
+            layer(
+                NinePatchBuilder::by_margins(40, 10, 10, 10),
+                0,
+                Color::BLACK,
+            ),
+            layer(margins(), -1, Color::RED),
+            layer(margins(), 0, Color::YELLOW),
+        ]);
+        app.world.entity_mut(root).insert(layers);
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let children = app.world.get::<Children>(root).unwrap();
+        let container = children
+            .iter()
+            .find(|child| app.world.get::<PatchContainer>(**child).is_some())
+            .unwrap();
+        // the `NinePatchData` is the first layer at 0, other layers at the same z keep their order
+        let layers: Vec<Entity> = app
+            .world
+            .get::<Children>(*container)
+            .unwrap()
+            .iter()
+            .copied()
+            .filter(|child| app.world.get::<PatchLayer>(*child).is_some())
+            .collect();
+        let tints: Vec<Color> = layers
+            .iter()
+            .map(|layer| app.world.get::<PatchLayer>(*layer).unwrap().tint)
+            .collect();
+        assert_eq!(
+            tints,
+            vec![
+                Color::RED,
+                Color::WHITE,
+                Color::GREEN,
+                Color::YELLOW,
+                Color::BLUE
+            ]
+        );
+        for (layer, tint) in layers.into_iter().zip(tints) {
+            let mut patches = 0;
+            for row in app.world.get::<Children>(layer).unwrap().iter() {
+                for patch in app.world.get::<Children>(*row).unwrap().iter() {
+                    let color = app.world.get::<BackgroundColor>(*patch).unwrap().0;
+                    assert_eq!(color, tint);
+                    patches += 1;
+                }
+            }
+            assert_eq!(patches, 9);
+        }
+    }
+
+    #[test]
+    fn outset_is_drawn_outside_of_the_node() {
+        let mut app = testing::app();
+        testing::camera(&mut app);
+        let builder =
+            NinePatchBuilder::by_margins(10, 10, 10, 10).with_outset(UiRect::all(Val::Px(20.)));
+        let root = testing::spawn(&mut app, builder, UVec2::new(30, 30), Vec2::new(100., 100.));
+        app.world.get_mut::<Style>(root).unwrap().margin = UiRect::all(Val::Px(100.));
+        app.world.entity_mut(root).insert(Interaction::None);
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let node_rect = |app: &App, entity: Entity| {
+            let node = app.world.get::<Node>(entity).unwrap();
+            node.logical_rect(app.world.get::<GlobalTransform>(entity).unwrap())
+        };
+        assert_eq!(node_rect(&app, root), Rect::new(100., 100., 200., 200.));
+        let container = app.world.get::<Children>(root).unwrap()[0];
+        let mut layers = app
+            .world
+            .get::<Children>(container)
+            .unwrap()
+            .iter()
+            .copied();
+        let layer = layers
+            .find(|child| app.world.get::<PatchLayer>(*child).is_some())
+            .unwrap();
+        assert_eq!(node_rect(&app, layer), Rect::new(80., 80., 220., 220.));
+        // the rows laying out the content are not moved by the outset
+        let mut rows_query = app.world.query::<(&NinePatchRow, &Parent)>();
+        let (_, parent) = rows_query
+            .iter(&app.world)
+            .find(|(row, parent)| row.root == root && parent.get() == container)
+            .unwrap();
+        assert_eq!(
+            node_rect(&app, parent.get()),
+            Rect::new(100., 100., 200., 200.)
+        );
+
+        // only the node reacts to the pointer
+        for (cursor, interaction) in [
+            (Vec2::new(90., 150.), Interaction::None),
+            (Vec2::new(110., 150.), Interaction::Hovered),
+            (Vec2::new(210., 150.), Interaction::None),
+        ] {
+            testing::move_cursor(&mut app, cursor);
+            app.update();
+            assert_eq!(
+                app.world.get::<Interaction>(root),
+                Some(&interaction),
+                "{cursor}"
+            );
+        }
+    }
+}
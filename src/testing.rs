@@ -0,0 +1,89 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    text::TextPlugin,
+    ui::UiPlugin,
+};
+
+use crate::{NinePatchBuilder, NinePatchData, NinePatchPlugin};
+
+/// Headless app running the UI layout, with a primary window of 1280x720
+pub(crate) fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        WindowPlugin::default(),
+        bevy::input::InputPlugin,
+        TransformPlugin,
+        HierarchyPlugin,
+    ))
+    // assets usually registered by the render plugins
+    .init_asset::<Image>()
+    .init_asset::<Shader>()
+    .init_asset::<TextureAtlas>()
+    .add_plugins((TextPlugin, UiPlugin, NinePatchPlugin::<()>::default()));
+    app
+}
+
+/// Texture of `size` filled with `color`
+pub(crate) fn texture(app: &mut App, size: UVec2, color: [u8; 4]) -> Handle<Image> {
+    let image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &color,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    app.world.resource_mut::<Assets<Image>>().add(image)
+}
+
+/// Spawn a nine patch of `size` from `builder` on a white texture of `texture_size`
+pub(crate) fn spawn(
+    app: &mut App,
+    builder: NinePatchBuilder,
+    texture_size: UVec2,
+    size: Vec2,
+) -> Entity {
+    let texture = texture(app, texture_size, [255; 4]);
+    let nine_patch = app
+        .world
+        .resource_mut::<Assets<NinePatchBuilder>>()
+        .add(builder);
+    app.world
+        .spawn((
+            Style {
+                width: Val::Px(size.x),
+                height: Val::Px(size.y),
+                ..default()
+            },
+            NinePatchData {
+                texture,
+                nine_patch,
+                ..default()
+            },
+        ))
+        .id()
+}
+
+/// Spawn a camera rendering the UI to the primary window, so that nodes react to the cursor. Without the render
+/// plugins, nodes are also marked as visible for the focus system
+pub(crate) fn camera(app: &mut App) {
+    app.world.spawn(Camera::default());
+    app.add_systems(PostUpdate, |mut nodes_query: Query<&mut ViewVisibility>| {
+        for mut visibility in nodes_query.iter_mut() {
+            visibility.set();
+        }
+    });
+}
+
+/// Move the cursor to `position` in the primary window
+pub(crate) fn move_cursor(app: &mut App, position: Vec2) {
+    let mut windows_query = app.world.query::<&mut Window>();
+    windows_query
+        .single_mut(&mut app.world)
+        .set_cursor_position(Some(position));
+}
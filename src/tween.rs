@@ -0,0 +1,320 @@
+use std::{f32::consts::PI, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{NinePatchBorderScale, NinePatchData, NinePatchTint};
+
+/// Easing curve of a tween, mapping its progress to how far it is between its start and end values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Start slowly
+    QuadraticIn,
+    /// End slowly
+    QuadraticOut,
+    /// Start and end slowly
+    QuadraticInOut,
+    /// Start slowly, more pronounced than quadratic
+    CubicIn,
+    /// End slowly, more pronounced than quadratic
+    CubicOut,
+    /// Start and end slowly, more pronounced than quadratic
+    CubicInOut,
+    /// Start and end slowly, following a sine
+    SineInOut,
+    /// Go slightly past the end value before settling on it, for elements popping up
+    BackOut,
+}
+
+impl Easing {
+    /// Eased value of `progress`, from 0 at the start to 1 at the end
+    pub fn ease(self, progress: f32) -> f32 {
+        let t = progress.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadraticIn => t * t,
+            Easing::QuadraticOut => 1. - (1. - t) * (1. - t),
+            Easing::QuadraticInOut if t < 0.5 => 2. * t * t,
+            Easing::QuadraticInOut => 1. - (-2. * t + 2.).powi(2) / 2.,
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1. - (1. - t).powi(3),
+            Easing::CubicInOut if t < 0.5 => 4. * t * t * t,
+            Easing::CubicInOut => 1. - (-2. * t + 2.).powi(3) / 2.,
+            Easing::SineInOut => (1. - (PI * t).cos()) / 2.,
+            Easing::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                1. + (OVERSHOOT + 1.) * (t - 1.).powi(3) + OVERSHOOT * (t - 1.).powi(2)
+            }
+        }
+    }
+}
+
+/// Values that can be tweened
+pub trait Tweenable: Clone + Copy + Send + Sync + 'static {
+    /// Value at `amount` between `self` at 0 and `end` at 1
+    fn lerp(self, end: Self, amount: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn lerp(self, end: Self, amount: f32) -> Self {
+        self + (end - self) * amount
+    }
+}
+
+impl Tweenable for Vec2 {
+    fn lerp(self, end: Self, amount: f32) -> Self {
+        Vec2::lerp(self, end, amount)
+    }
+}
+
+impl Tweenable for Color {
+    fn lerp(self, end: Self, amount: f32) -> Self {
+        let start = Vec4::from(self.as_rgba_f32());
+        let end = Vec4::from(end.as_rgba_f32());
+        let [r, g, b, a] = start.lerp(end, amount).to_array();
+        Color::rgba(r, g, b, a)
+    }
+}
+
+/// Change of a value from `start` to `end` over `duration`
+#[derive(Debug, Clone)]
+pub struct Tween<V: Tweenable> {
+    /// Value at the start of the tween
+    pub start: V,
+    /// Value at the end of the tween
+    pub end: V,
+    /// Duration of the tween
+    pub duration: Duration,
+    /// Easing curve of the tween
+    pub easing: Easing,
+    /// Time since the start of the tween
+    pub elapsed: Duration,
+}
+
+impl<V: Tweenable> Tween<V> {
+    /// Tween from `start` to `end` over `duration`
+    pub fn new(start: V, end: V, duration: Duration, easing: Easing) -> Self {
+        Tween {
+            start,
+            end,
+            duration,
+            easing,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Current value of the tween
+    pub fn value(&self) -> V {
+        let progress = if self.duration.is_zero() {
+            1.
+        } else {
+            self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+        };
+        self.start.lerp(self.end, self.easing.ease(progress))
+    }
+
+    /// Has the tween reached its end
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn tick(&mut self, delta: Duration) -> V {
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+        self.value()
+    }
+}
+
+/// Tween the size of a 9-Patch UI element in pixels, added on the entity holding the `NinePatchData`. It sets the
+/// `width` and `height` of its `Style`, and all patches follow
+#[derive(Debug, Clone, Component)]
+pub struct NinePatchSizeTween(pub Tween<Vec2>);
+
+/// Tween the tint of a 9-Patch UI element, added on the entity holding the `NinePatchData`. It sets its
+/// `NinePatchTint`
+#[derive(Debug, Clone, Component)]
+pub struct NinePatchTintTween(pub Tween<Color>);
+
+/// Tween the scale of the fixed patches of a 9-Patch UI element, added on the entity holding the `NinePatchData`. It
+/// sets its `NinePatchBorderScale`
+#[derive(Debug, Clone, Component)]
+pub struct NinePatchBorderScaleTween(pub Tween<f32>);
+
+/// Property of a 9-Patch UI element changed by a tween
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NinePatchTweenProperty {
+    /// [`NinePatchSizeTween`]
+    Size,
+    /// [`NinePatchTintTween`]
+    Tint,
+    /// [`NinePatchBorderScaleTween`]
+    BorderScale,
+}
+
+/// Event sent when a tween of a 9-Patch UI element reaches its end. The tween component is removed
+#[derive(Debug, Clone, Copy, Event)]
+pub struct NinePatchTweenCompleted {
+    /// Entity holding the `NinePatchData`
+    pub entity: Entity,
+    /// Property that was tweened
+    pub property: NinePatchTweenProperty,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn tween_ninepatches<
+    T: Clone + Send + Sync + Eq + std::hash::Hash + TypePath + 'static,
+>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sizes_query: Query<(Entity, &mut NinePatchSizeTween, &mut Style), With<NinePatchData<T>>>,
+    mut tints_query: Query<
+        (Entity, &mut NinePatchTintTween, Option<&mut NinePatchTint>),
+        With<NinePatchData<T>>,
+    >,
+    mut scales_query: Query<
+        (
+            Entity,
+            &mut NinePatchBorderScaleTween,
+            Option<&mut NinePatchBorderScale>,
+        ),
+        With<NinePatchData<T>>,
+    >,
+    mut completed: EventWriter<NinePatchTweenCompleted>,
+) {
+    let delta = time.delta();
+    let mut complete = |entity: Entity, property| {
+        completed.send(NinePatchTweenCompleted { entity, property });
+    };
+
+    for (entity, mut tween, mut style) in sizes_query.iter_mut() {
+        let size = tween.0.tick(delta);
+        style.width = Val::Px(size.x);
+        style.height = Val::Px(size.y);
+        if tween.0.finished() {
+            commands.entity(entity).remove::<NinePatchSizeTween>();
+            complete(entity, NinePatchTweenProperty::Size);
+        }
+    }
+    for (entity, mut tween, tint) in tints_query.iter_mut() {
+        let color = tween.0.tick(delta);
+        match tint {
+            Some(mut tint) => tint.0 = color,
+            None => {
+                commands.entity(entity).insert(NinePatchTint(color));
+            }
+        }
+        if tween.0.finished() {
+            commands.entity(entity).remove::<NinePatchTintTween>();
+            complete(entity, NinePatchTweenProperty::Tint);
+        }
+    }
+    for (entity, mut tween, scale) in scales_query.iter_mut() {
+        let value = tween.0.tick(delta);
+        match scale {
+            Some(mut scale) => scale.0 = value,
+            None => {
+                commands.entity(entity).insert(NinePatchBorderScale(value));
+            }
+        }
+        if tween.0.finished() {
+            commands
+                .entity(entity)
+                .remove::<NinePatchBorderScaleTween>();
+            complete(entity, NinePatchTweenProperty::BorderScale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{ecs::event::ManualEventReader, prelude::*, time::TimeUpdateStrategy};
+
+    use super::{
+        Easing, NinePatchSizeTween, NinePatchTweenCompleted, NinePatchTweenProperty, Tween,
+    };
+    use crate::{testing, NinePatchBuilder};
+
+    const EASINGS: [Easing; 9] = [
+        Easing::Linear,
+        Easing::QuadraticIn,
+        Easing::QuadraticOut,
+        Easing::QuadraticInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::SineInOut,
+        Easing::BackOut,
+    ];
+
+    #[test]
+    fn easings_start_at_0_and_end_at_1() {
+        for easing in EASINGS {
+            assert!(easing.ease(0.).abs() < 1e-6, "{easing:?}");
+            assert!((easing.ease(1.) - 1.).abs() < 1e-6, "{easing:?}");
+            // progress is clamped
+            assert_eq!(easing.ease(-1.), easing.ease(0.), "{easing:?}");
+            assert_eq!(easing.ease(2.), easing.ease(1.), "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn easings_are_monotonic() {
+        let steps = (0..=100).map(|step| step as f32 / 100.);
+        for easing in EASINGS {
+            let values: Vec<f32> = steps.clone().map(|t| easing.ease(t)).collect();
+            if easing == Easing::BackOut {
+                // goes past the end value before settling on it
+                assert!(values.iter().any(|value| *value > 1.));
+                continue;
+            }
+            assert!(
+                values.windows(2).all(|pair| pair[0] <= pair[1]),
+                "{easing:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn size_tween_reaches_its_end_once() {
+        let mut app = testing::app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+        let root = testing::spawn(
+            &mut app,
+            NinePatchBuilder::by_margins(10, 10, 10, 10),
+            UVec2::new(30, 30),
+            Vec2::new(100., 100.),
+        );
+        app.world
+            .entity_mut(root)
+            .insert(NinePatchSizeTween(Tween::new(
+                Vec2::new(100., 100.),
+                Vec2::new(200., 50.),
+                Duration::from_millis(250),
+                Easing::QuadraticInOut,
+            )));
+
+        let mut reader = ManualEventReader::<NinePatchTweenCompleted>::default();
+        let mut completed = vec![];
+        for _ in 0..6 {
+            app.update();
+            let events = app.world.resource::<Events<NinePatchTweenCompleted>>();
+            completed.extend(reader.read(events).copied());
+        }
+
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].entity, root);
+        assert_eq!(completed[0].property, NinePatchTweenProperty::Size);
+        assert!(app.world.get::<NinePatchSizeTween>(root).is_none());
+        let style = app.world.get::<Style>(root).unwrap();
+        assert_eq!((style.width, style.height), (Val::Px(200.), Val::Px(50.)));
+        assert_eq!(
+            app.world.get::<Node>(root).unwrap().size(),
+            Vec2::new(200., 50.)
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{NinePatchBuilder, NinePatchError};
+
+fn invalid(reason: impl Into<String>) -> NinePatchError {
+    NinePatchError::InvalidUnityMeta(reason.into())
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Values of an inline mapping like `{x: 1, y: 2, z: 3, w: 4}`
+fn inline_mapping(value: &str) -> HashMap<&str, &str> {
+    value
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+        .filter_map(|entry| entry.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+fn number(value: &str) -> Result<f32, NinePatchError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| invalid(format!("expected a number, found {value}")))
+}
+
+/// Border of a sprite, as left, bottom, right, top
+fn border(value: &str) -> Result<[u32; 4], NinePatchError> {
+    let border = inline_mapping(value);
+    let mut sides = [0; 4];
+    for (side, key) in sides.iter_mut().zip(["x", "y", "z", "w"]) {
+        let value = border
+            .get(key)
+            .ok_or_else(|| invalid(format!("invalid sprite border {value}")))?;
+        *side = number(value)?.round() as u32;
+    }
+    Ok(sides)
+}
+
+#[derive(Debug, Default)]
+struct Sprite {
+    name: String,
+    rect: [f32; 4],
+    border: [u32; 4],
+}
+
+impl Sprite {
+    fn builder(&self, texture_size: UVec2) -> Result<NinePatchBuilder<String>, NinePatchError> {
+        let [x, y, width, height] = self.rect.map(|value| value.round() as i64);
+        // rectangles of sprites start from the bottom of the texture
+        let top = texture_size.y as i64 - y - height;
+        if x < 0 || top < 0 || width < 0 || height < 0 || x + width > texture_size.x as i64 {
+            return Err(invalid(format!(
+                "sprite {} is outside of the texture",
+                self.name
+            )));
+        }
+        let [left, bottom, right, top_border] = self.border;
+        Ok(NinePatchBuilder::by_margins_with_content(
+            top_border,
+            bottom,
+            left,
+            right,
+            self.name.clone(),
+        )
+        .with_region(URect::new(
+            x as u32,
+            top as u32,
+            (x + width) as u32,
+            (top + height) as u32,
+        )))
+    }
+}
+
+impl NinePatchBuilder<String> {
+    /// Create a `NinePatchBuilder` for each sprite with a border in a Unity texture `.meta` file, by sprite name. The
+    /// content zone of each is named after its sprite. `texture_size` is the size of the texture, and `name` the
+    /// name of the sprite when the texture is a single sprite.
+    pub fn from_unity_meta(
+        meta: &str,
+        name: &str,
+        texture_size: UVec2,
+    ) -> Result<HashMap<String, Self>, NinePatchError> {
+        let mut single_border = None;
+        let mut multiple = false;
+        let mut sprites: Vec<Sprite> = vec![];
+        // indentation of the list of sprites, and of the `rect` of the current sprite
+        let mut sprites_indentation = None;
+        let mut rect_indentation = None;
+        for line in meta.lines() {
+            let indentation = indentation(line);
+            let Some((key, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if let Some(sprites_indentation) = sprites_indentation {
+                if indentation < sprites_indentation
+                    || (indentation == sprites_indentation && !key.starts_with('-'))
+                {
+                    // end of the list of sprites
+                    break;
+                }
+                if indentation == sprites_indentation {
+                    sprites.push(Sprite::default());
+                    rect_indentation = None;
+                }
+                let key = key.trim_start_matches('-').trim();
+                let Some(sprite) = sprites.last_mut() else {
+                    continue;
+                };
+                match rect_indentation {
+                    Some(rect_indentation) if indentation > rect_indentation => {
+                        let index = match key {
+                            "x" => 0,
+                            "y" => 1,
+                            "width" => 2,
+                            "height" => 3,
+                            _ => continue,
+                        };
+                        sprite.rect[index] = number(value)?;
+                        continue;
+                    }
+                    _ => rect_indentation = None,
+                }
+                // fields of a sprite are one level below the list
+                if indentation > sprites_indentation + 2 {
+                    continue;
+                }
+                match key {
+                    "name" => sprite.name = value.to_string(),
+                    "rect" => rect_indentation = Some(indentation),
+                    "border" => sprite.border = border(value)?,
+                    _ => (),
+                }
+                continue;
+            }
+            match key {
+                "spriteMode" => multiple = value == "2",
+                "spriteBorder" => single_border = Some(border(value)?),
+                "sprites" if value.is_empty() => sprites_indentation = Some(indentation),
+                _ => (),
+            }
+        }
+
+        if !multiple {
+            let border =
+                single_border.ok_or_else(|| invalid("the texture has no sprite border"))?;
+            sprites = vec![Sprite {
+                name: name.to_string(),
+                rect: [0., 0., texture_size.x as f32, texture_size.y as f32],
+                border,
+            }];
+        }
+        sprites
+            .into_iter()
+            // sprites without border aren't nine patches
+            .filter(|sprite| sprite.border != [0; 4])
+            .map(|sprite| Ok((sprite.name.clone(), sprite.builder(texture_size)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{NinePatchBuilder, PatchSize};
+
+    /// Top, bottom, left and right margins of a builder
+    fn margins(builder: &NinePatchBuilder<String>) -> [PatchSize; 4] {
+        [
+            builder.patches[0][0].original_height,
+            builder.patches[2][0].original_height,
+            builder.patches[0][0].original_width,
+            builder.patches[0][2].original_width,
+        ]
+    }
+
+    #[test]
+    fn single_sprite() {
+        let meta = "fileFormatVersion: 2
+guid: 5b3c1f2a9e8d4c7b6a5f4e3d2c1b0a99
+TextureImporter:
+  spriteMode: 1
+  spritePivot: {x: 0.5, y: 0.5}
+  spritePixelsToUnits: 100
+  spriteBorder: {x: 6, y: 10, z: 6, w: 5}
+  spriteGenerateFallbackPhysicsShape: 1
+";
+        let builders =
+            NinePatchBuilder::from_unity_meta(meta, "button", UVec2::new(48, 32)).unwrap();
+        let builder = &builders["button"];
+        assert_eq!(
+            margins(builder),
+            [5, 10, 6, 6].map(PatchSize::Fixed),
+            "borders are left, bottom, right, top"
+        );
+        assert_eq!(builder.region, Some(URect::new(0, 0, 48, 32)));
+        assert_eq!(builder.patches[1][1].content.as_deref(), Some("button"));
+    }
+
+    #[test]
+    fn multiple_sprites() {
+        let meta = "TextureImporter:
+  spriteMode: 2
+  spriteSheet:
+    serializedVersion: 2
+    sprites:
+    - serializedVersion: 2
+      name: button
+      rect:
+        serializedVersion: 2
+        x: 0
+        y: 64
+        width: 48
+        height: 32
+      alignment: 0
+      pivot: {x: 0.5, y: 0.5}
+      border: {x: 4, y: 8, z: 4, w: 6}
+    - serializedVersion: 2
+      name: icon
+      rect:
+        serializedVersion: 2
+        x: 48
+        y: 0
+        width: 16
+        height: 16
+      alignment: 0
+      pivot: {x: 0.5, y: 0.5}
+      border: {x: 0, y: 0, z: 0, w: 0}
+    outline: []
+  spritePixelsToUnits: 100
+";
+        let builders = NinePatchBuilder::from_unity_meta(meta, "ui", UVec2::new(64, 96)).unwrap();
+        // the icon has no border
+        assert_eq!(builders.len(), 1);
+        let builder = &builders["button"];
+        assert_eq!(margins(builder), [6, 8, 4, 4].map(PatchSize::Fixed));
+        // the sprite is at the top of the texture, its rect starts from the bottom
+        assert_eq!(builder.region, Some(URect::new(0, 0, 48, 32)));
+    }
+}